@@ -0,0 +1,201 @@
+// derive(Getters) and derive(Setters) share the field analysis of the builder:
+// the same `FieldData` decides which fields are optional or repeated, and the
+// same `#[builder(...)]` attributes apply.
+
+use crate::{combine_error, named_fields, process_fields, skip_meta_value};
+use crate::{struct_attrs, unknown_attr};
+use crate::{FieldData, Repeatable, Roots};
+
+struct GetterAttrs {
+    copy: bool,
+    skip: bool,
+}
+
+const GETTER_ATTRS: &[&str] = &["copy", "skip"];
+
+impl FieldData {
+    /// `x(&self)` returns the field by reference, as `Option<&T>` for optional
+    /// fields and as a slice for `each` fields, or by value under
+    /// `#[getter(copy)]`. `x_mut(&mut self)` returns the field itself.
+    fn generate_getter(
+        &self,
+        roots: &Roots,
+        attrs: &GetterAttrs,
+    ) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = self.ident.as_ref().unwrap();
+        let ty = &self.ty;
+        let field_ty = &self.field_ty;
+        let (ret_ty, value) = if attrs.copy {
+            (::quote::quote!(#field_ty), ::quote::quote!(self.#ident))
+        } else if self.optional {
+            (
+                ::quote::quote!(#core::option::Option<&#ty>),
+                ::quote::quote!(self.#ident.as_ref()),
+            )
+        } else if let (
+            Repeatable::SingularOnly(_, element_ty)
+            | Repeatable::SingularAndPlural(_, element_ty),
+            false,
+        ) = (&self.repeat, self.custom_collection)
+        {
+            (
+                ::quote::quote!(&[#element_ty]),
+                ::quote::quote!(&self.#ident),
+            )
+        } else {
+            (::quote::quote!(&#field_ty), ::quote::quote!(&self.#ident))
+        };
+        let mut_ident = ::quote::format_ident!("{}_mut", ident);
+        ::quote::quote!(
+            pub fn #ident(&self) -> #ret_ty {
+                #value
+            }
+
+            pub fn #mut_ident(&mut self) -> &mut #field_ty {
+                &mut self.#ident
+            }
+        )
+    }
+
+    /// `set_x` takes the same argument as the builder's setter, and `each`
+    /// fields also get a `push_<each>` method.
+    fn generate_field_setter(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let ident = self.ident.as_ref().unwrap();
+        let set_ident = ::quote::format_ident!("set_{}", ident);
+        let (setter_ty, value) = self.setter_input(roots);
+        let mut setters = ::quote::quote!(
+            pub fn #set_ident(&mut self, #ident: #setter_ty) -> &mut Self {
+                self.#ident = #value;
+                self
+            }
+        );
+        if let Repeatable::SingularOnly(s, element_ty)
+        | Repeatable::SingularAndPlural(s, element_ty) = &self.repeat
+        {
+            let push_ident = ::quote::format_ident!("push_{}", s);
+            let (element_ty, element) = self.element_input(s, element_ty);
+            let core = &roots.core;
+            let collection = if self.optional {
+                ::quote::quote!(self.#ident.get_or_insert_with(#core::default::Default::default))
+            } else {
+                ::quote::quote!(&mut self.#ident)
+            };
+            let push = self.generate_push(roots, collection, element);
+            let bound = self.generate_each_bound(roots).into_iter();
+            setters.extend(::quote::quote!(
+                pub fn #push_ident(&mut self, #s: #element_ty) -> &mut Self
+                where
+                    #(#bound)*
+                {
+                    #push;
+                    self
+                }
+            ));
+        }
+        setters
+    }
+}
+
+pub(crate) fn expand_getters(input: &::syn::DeriveInput) -> ::proc_macro2::TokenStream {
+    let name = &input.ident;
+    let (struct_attrs, mut errors) = struct_attrs(&input.attrs);
+    let roots = Roots::new(struct_attrs.no_std);
+    let fields = match named_fields(input, "Getters") {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error(),
+    };
+    let mut getters = ::std::vec::Vec::new();
+    for (field, (field_data, error)) in
+        fields.iter().zip(process_fields(fields.clone(), &roots))
+    {
+        if let Some(e) = error {
+            combine_error(&mut errors, e);
+        }
+        let (attrs, error) = getter_attrs(&field.attrs);
+        if let Some(e) = error {
+            combine_error(&mut errors, e);
+        }
+        if !attrs.skip {
+            getters.push(field_data.generate_getter(&roots, &attrs));
+        }
+    }
+    let errors = errors.map(|e| e.to_compile_error());
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    ::quote::quote!(
+        #errors
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#getters)*
+        }
+    )
+}
+
+pub(crate) fn expand_setters(input: &::syn::DeriveInput) -> ::proc_macro2::TokenStream {
+    let name = &input.ident;
+    let (struct_attrs, mut errors) = struct_attrs(&input.attrs);
+    let roots = Roots::new(struct_attrs.no_std);
+    let fields = match named_fields(input, "Setters") {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error(),
+    };
+    let mut setters = ::std::vec::Vec::new();
+    for (field_data, error) in process_fields(fields, &roots) {
+        if let Some(e) = error {
+            combine_error(&mut errors, e);
+        }
+        // Fields skipped by the builder are internal state that callers must
+        // not set, so they get no setter here either.
+        if field_data.skip.is_none() {
+            setters.push(field_data.generate_field_setter(&roots));
+        }
+    }
+    let errors = errors.map(|e| e.to_compile_error());
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    ::quote::quote!(
+        #errors
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#setters)*
+        }
+    )
+}
+
+fn getter_attrs(
+    attrs: &[::syn::Attribute],
+) -> (GetterAttrs, ::std::option::Option<::syn::Error>) {
+    let mut getter_attrs = GetterAttrs {
+        copy: false,
+        skip: false,
+    };
+    let mut errors: ::std::option::Option<::syn::Error> = None;
+    for attr in attrs {
+        if !attr.path().is_ident("getter") {
+            continue;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("copy") {
+                getter_attrs.copy = true;
+            } else if meta.path.is_ident("skip") {
+                getter_attrs.skip = true;
+            } else {
+                combine_error(
+                    &mut errors,
+                    unknown_attr(&meta.path, "getter", GETTER_ATTRS),
+                );
+                skip_meta_value(&meta)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = parsed {
+            combine_error(&mut errors, e);
+        }
+    }
+    (getter_attrs, errors)
+}
@@ -0,0 +1,1160 @@
+use ::syn::spanned::Spanned;
+
+mod accessors;
+
+/// Crate roots for the paths in the generated code: `::std` for both by
+/// default, or `::core` and `::alloc` under `#[builder(no_std)]`.
+struct Roots {
+    core: ::proc_macro2::TokenStream,
+    alloc: ::proc_macro2::TokenStream,
+    no_std: bool,
+}
+
+impl Roots {
+    pub fn new(no_std: bool) -> Self {
+        if no_std {
+            Roots {
+                core: ::quote::quote!(::core),
+                alloc: ::quote::quote!(::alloc),
+                no_std,
+            }
+        } else {
+            Roots {
+                core: ::quote::quote!(::std),
+                alloc: ::quote::quote!(::std),
+                no_std,
+            }
+        }
+    }
+}
+
+/// A conversion applied by a setter to the value it is given: allocating the
+/// `Box`, `Arc` or `Rc` of a `setter(wrap)` field, or `Into` for `Cow` fields.
+/// Setters taking a whole `Option` through `setter(strip_option = false)` pass
+/// it through unconverted.
+struct Wrapper {
+    constructor: ::proc_macro2::TokenStream,
+    param_ty: ::proc_macro2::TokenStream,
+}
+
+enum Repeatable {
+    No,
+    SingularOnly(::proc_macro2::Ident, ::syn::Type),
+    SingularAndPlural(::proc_macro2::Ident, ::syn::Type),
+}
+
+struct FieldData {
+    ident: Option<::syn::Ident>,
+    ty: ::syn::Type,
+    field_ty: ::syn::Type,
+    repeat: Repeatable,
+    optional: bool,
+    strip_option: bool,
+    skip: ::std::option::Option<::syn::Expr>,
+    wrap: ::std::option::Option<Wrapper>,
+    default_with: ::std::option::Option<::syn::Path>,
+    /// Set when the element type of an `each` field was given with `item`, in
+    /// which case the collection is only known to be `Default + Extend<item>`.
+    custom_collection: bool,
+}
+
+impl FieldData {
+    /// Every field starts out unset, `each` fields included, so that the
+    /// builder can tell which fields were given.
+    pub fn generate_builder_field(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        ::quote::quote!(#ident: #core::option::Option::None)
+    }
+
+    /// The value an `each` field is built with from `stored`, the collection
+    /// on the builder. A collection that was never given starts out empty.
+    pub fn generate_repeated_value(
+        &self,
+        roots: &Roots,
+        stored: ::proc_macro2::TokenStream,
+    ) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let value = ::quote::quote!(#core::option::Option::unwrap_or_default(#stored));
+        if self.optional {
+            ::quote::quote!(#core::option::Option::Some(#value))
+        } else {
+            value
+        }
+    }
+
+    /// The parameter type of the setter for this field, and the expression
+    /// converting that parameter into a value of the field's own type.
+    pub fn setter_input(
+        &self,
+        roots: &Roots,
+    ) -> (::proc_macro2::TokenStream, ::proc_macro2::TokenStream) {
+        let core = &roots.core;
+        let ident = &self.ident;
+        let ty = &self.ty;
+        // With `setter(strip_option = false)` the setter takes the field's own
+        // `Option<T>`: converting it as well would leave a bare `None` with
+        // nothing to infer the `impl Trait` parameter from.
+        if self.optional && !self.strip_option {
+            return (
+                ::quote::quote!(#core::option::Option<#ty>),
+                ::quote::quote!(#ident),
+            );
+        }
+        // The setter takes the wrapped value and converts it; see `Wrapper`.
+        let (param_ty, wrapped) = match (&self.wrap, &self.repeat) {
+            (
+                Some(Wrapper {
+                    param_ty,
+                    constructor,
+                }),
+                Repeatable::No,
+            ) => (param_ty.clone(), ::quote::quote!(#constructor(#ident))),
+            _ => (::quote::quote!(#ty), ::quote::quote!(#ident)),
+        };
+        // Otherwise the setter of an `Option<T>` field takes `T` and the field
+        // can only be left unset.
+        if self.optional {
+            (
+                param_ty,
+                ::quote::quote!(#core::option::Option::Some(#wrapped)),
+            )
+        } else {
+            (param_ty, wrapped)
+        }
+    }
+
+    /// Like `setter_input`, for the singular setter of an `each` field.
+    pub fn element_input(
+        &self,
+        singular: &::proc_macro2::Ident,
+        element_ty: &::syn::Type,
+    ) -> (::proc_macro2::TokenStream, ::proc_macro2::TokenStream) {
+        match &self.wrap {
+            Some(Wrapper {
+                param_ty,
+                constructor,
+            }) => (param_ty.clone(), ::quote::quote!(#constructor(#singular))),
+            None => (::quote::quote!(#element_ty), ::quote::quote!(#singular)),
+        }
+    }
+
+    pub fn generate_setter(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        let (setter_ty, field_value) = self.setter_input(roots);
+        // Optional fields are stored on the builder with their own type.
+        let value = if self.optional {
+            field_value
+        } else {
+            ::quote::quote!(#core::option::Option::Some(#field_value))
+        };
+        match &self.repeat {
+            Repeatable::No => {
+                ::quote::quote!(
+                    pub fn #ident(&mut self, #ident: #setter_ty) -> &mut Self{
+                        self.#ident = #value;
+                        self
+                    }
+                )
+            },
+            Repeatable::SingularOnly(s, new_ty) => {
+                let (new_ty, element) = self.element_input(s, new_ty);
+                let push = self.generate_push(
+                    roots,
+                    ::quote::quote!(self.#ident.get_or_insert_with(#core::default::Default::default)),
+                    element,
+                );
+                ::quote::quote!(
+                    pub fn #s(&mut self, #s: #new_ty) -> &mut Self {
+                        #push;
+                        self
+                    }
+                )
+            },
+            Repeatable::SingularAndPlural(s, new_ty) => {
+                let (new_ty, element) = self.element_input(s, new_ty);
+                let push = self.generate_push(
+                    roots,
+                    ::quote::quote!(self.#ident.get_or_insert_with(#core::default::Default::default)),
+                    element,
+                );
+                ::quote::quote!(
+                    pub fn #ident(&mut self, #ident: #setter_ty) -> &mut Self {
+                        self.#ident = #value;
+                        self
+                    }
+
+                    pub fn #s(&mut self, #s: #new_ty) -> &mut Self {
+                        #push;
+                        self
+                    }
+                )
+            },
+        }
+    }
+
+    pub fn generate_closure_setter(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let mut closure_setters = ::proc_macro2::TokenStream::new();
+        if !matches!(self.repeat, Repeatable::No) {
+            let with_ident = ::quote::format_ident!("{}_with", ident.as_ref().unwrap());
+            closure_setters.extend(::quote::quote!(
+                pub fn #with_ident(&mut self, f: impl #core::ops::FnOnce(&mut #ty)) -> &mut Self {
+                    f(self.#ident.get_or_insert_with(#core::default::Default::default));
+                    self
+                }
+            ));
+        }
+        if self.optional {
+            let map_ident = ::quote::format_ident!("{}_map", ident.as_ref().unwrap());
+            closure_setters.extend(::quote::quote!(
+                pub fn #map_ident(
+                    &mut self,
+                    f: impl #core::ops::FnOnce(#core::option::Option<#ty>) -> #core::option::Option<#ty>,
+                ) -> &mut Self {
+                    self.#ident = f(self.#ident.take());
+                    self
+                }
+            ));
+        }
+        closure_setters
+    }
+
+    /// Adds one element to the collection behind `collection`. `push` lets a
+    /// `Box<impl Trait>` coerce into the `Box<dyn Trait>` element of a vector;
+    /// other collections are only known to implement `Extend`.
+    pub fn generate_push(
+        &self,
+        roots: &Roots,
+        collection: ::proc_macro2::TokenStream,
+        element: ::proc_macro2::TokenStream,
+    ) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        if self.custom_collection {
+            ::quote::quote!(
+                #core::iter::Extend::extend(#collection, #core::option::Option::Some(#element))
+            )
+        } else {
+            ::quote::quote!((#collection).push(#element))
+        }
+    }
+
+    /// The builder starts every `each` collection from `Default`, and fills one
+    /// named through `item` with `Extend`. Such a collection may be a type
+    /// parameter or alias, so the builder carries these bounds for it, as
+    /// deferred bounds checked where the builder is used.
+    pub fn generate_each_bound(
+        &self,
+        roots: &Roots,
+    ) -> ::std::option::Option<::syn::WherePredicate> {
+        let core = &roots.core;
+        let ty = &self.ty;
+        match &self.repeat {
+            Repeatable::SingularOnly(_, item)
+            | Repeatable::SingularAndPlural(_, item)
+                if self.custom_collection =>
+            {
+                Some(deferred_bound(
+                    ty,
+                    ::quote::quote!(#core::default::Default + #core::iter::Extend<#item>),
+                ))
+            },
+            _ => None,
+        }
+    }
+
+    /// The value of this field in `build_async`. A field left unset is filled in
+    /// by awaiting its `default_with` initializer, whose error is converted
+    /// into the builder's error type.
+    pub fn generate_async_field(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        let value = match (&self.default_with, self.optional) {
+            _ if !matches!(self.repeat, Repeatable::No) => {
+                self.generate_repeated_value(roots, ::quote::quote!(self.#ident))
+            },
+            (Some(default_with), false) => ::quote::quote!(match self.#ident {
+                #core::option::Option::Some(#ident) => #ident,
+                #core::option::Option::None => #default_with().await?,
+            }),
+            (Some(default_with), true) => ::quote::quote!(match self.#ident {
+                #core::option::Option::Some(#ident) => #core::option::Option::Some(#ident),
+                #core::option::Option::None => #core::option::Option::Some(#default_with().await?),
+            }),
+            (None, false) => ::quote::quote!(self.#ident.ok_or_else(|| {
+                ::derive_builder::UninitializedFieldError::new(stringify!(#ident))
+            })?),
+            (None, true) => ::quote::quote!(self.#ident),
+        };
+        ::quote::quote!(#ident: #value)
+    }
+
+    pub fn generate_patch_field(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        let field_ty = &self.field_ty;
+        ::quote::quote!(pub #ident: #core::option::Option<#field_ty>)
+    }
+
+    pub fn generate_diff(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        ::quote::quote!(
+            #ident: if #core::cmp::PartialEq::ne(&self.#ident, &other.#ident) {
+                #core::option::Option::Some(#core::clone::Clone::clone(&other.#ident))
+            } else {
+                #core::option::Option::None
+            }
+        )
+    }
+
+    pub fn generate_apply(&self, roots: &Roots) -> ::proc_macro2::TokenStream {
+        let core = &roots.core;
+        let ident = &self.ident;
+        ::quote::quote!(
+            if let #core::option::Option::Some(#ident) = patch.#ident {
+                self.#ident = #ident;
+            }
+        )
+    }
+}
+
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive(input: proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+    let input = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
+    let name = &input.ident;
+    let builder_name = ::quote::format_ident!("{}Builder", name);
+    let patch_name = ::quote::format_ident!("{}Patch", name);
+    let fields_info = match named_fields(&input, "Builder") {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let (struct_attrs, struct_error) = struct_attrs(&input.attrs);
+    let roots = Roots::new(struct_attrs.no_std);
+    let Roots { core, alloc, .. } = &roots;
+    // Without `std` there is no `dyn Error` to box, so `build` returns the
+    // error type from the runtime crate directly.
+    let error_ty = if struct_attrs.no_std {
+        ::quote::quote!(::derive_builder::UninitializedFieldError)
+    } else {
+        ::quote::quote!(::std::boxed::Box<dyn ::std::error::Error>)
+    };
+    let (fields_data, possible_errors): (
+        ::std::vec::Vec<FieldData>,
+        ::std::vec::Vec<::std::option::Option<::syn::Error>>,
+    ) = process_fields(fields_info, &roots).into_iter().unzip();
+    // Skipped fields never appear on the builder or in the patch; `build`
+    // fills them in from their default expression. Both structs carry a
+    // `__phantom` marker instead, so that generic parameters only a skipped
+    // field mentions are still used.
+    let (skipped_fields, fields_data): (
+        ::std::vec::Vec<FieldData>,
+        ::std::vec::Vec<FieldData>,
+    ) = fields_data.into_iter().partition(|f| f.skip.is_some());
+    let skipped_names: ::std::vec::Vec<&::std::option::Option<::proc_macro2::Ident>> =
+        skipped_fields.iter().map(|f| &f.ident).collect();
+    let skipped_values: ::std::vec::Vec<&::syn::Expr> =
+        skipped_fields.iter().flat_map(|f| &f.skip).collect();
+    let errors = ::std::iter::once(struct_error)
+        .chain(possible_errors)
+        .flatten()
+        .reduce(|mut errors, e| {
+            errors.combine(e);
+            errors
+        })
+        .map(|e| e.to_compile_error());
+    let fields_names: ::std::vec::Vec<::std::option::Option<::proc_macro2::Ident>> =
+        fields_data.iter().map(|f| f.ident.clone()).collect();
+    let fields_types = fields_data.iter().map(|f| f.ty.clone());
+    let (repeated_fields, single_fields): (
+        ::std::vec::Vec<&FieldData>,
+        ::std::vec::Vec<&FieldData>,
+    ) = fields_data
+        .iter()
+        .partition(|f| !matches!(f.repeat, Repeatable::No));
+    let optional_fields: ::std::vec::Vec<::std::option::Option<::proc_macro2::Ident>> =
+        single_fields
+            .iter()
+            .filter(|f| f.optional)
+            .map(|f| f.ident.clone())
+            .collect();
+    let required_fields: ::std::vec::Vec<::std::option::Option<::proc_macro2::Ident>> =
+        single_fields
+            .iter()
+            .filter(|f| !f.optional)
+            .map(|f| f.ident.clone())
+            .collect();
+    let repeated_names: ::std::vec::Vec<&::std::option::Option<::proc_macro2::Ident>> =
+        repeated_fields.iter().map(|f| &f.ident).collect();
+    let repeated_clones: ::std::vec::Vec<::proc_macro2::TokenStream> = repeated_fields
+        .iter()
+        .map(|f| {
+            let ident = &f.ident;
+            f.generate_repeated_value(
+                &roots,
+                ::quote::quote!(#core::clone::Clone::clone(&self.#ident)),
+            )
+        })
+        .collect();
+    let repeated_values: ::std::vec::Vec<::proc_macro2::TokenStream> = repeated_fields
+        .iter()
+        .map(|f| {
+            let ident = &f.ident;
+            f.generate_repeated_value(&roots, ::quote::quote!(self.#ident))
+        })
+        .collect();
+    let setters: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_setter(&roots))
+        .collect();
+    let closure_setters: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_closure_setter(&roots))
+        .collect();
+    let builder_fields: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_builder_field(&roots))
+        .collect();
+    let patch_fields: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_patch_field(&roots))
+        .collect();
+    let diffs: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_diff(&roots))
+        .collect();
+    let async_fields: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_async_field(&roots))
+        .collect();
+    let applies: ::std::vec::Vec<::proc_macro2::TokenStream> = fields_data
+        .iter()
+        .map(|f| f.generate_apply(&roots))
+        .collect();
+    // Only the borrowing `build` and the builder's `Clone` impl clone fields,
+    // so fields which are not `Clone` can still be built through
+    // `build_owned`/`take`.
+    let clone_bounds: ::std::vec::Vec<::syn::WherePredicate> = fields_data
+        .iter()
+        .map(|f| deferred_bound(&f.ty, ::quote::quote!(#core::clone::Clone)))
+        .collect();
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut builder_generics = generics.clone();
+    builder_generics.make_where_clause().predicates.extend(
+        fields_data
+            .iter()
+            .flat_map(|f| f.generate_each_bound(&roots)),
+    );
+    let builder_where_clause = &builder_generics.where_clause;
+    let mut clone_generics = builder_generics.clone();
+    clone_generics
+        .make_where_clause()
+        .predicates
+        .extend(clone_bounds.iter().cloned());
+    let clone_where_clause = &clone_generics.where_clause;
+    // `diff` needs every field to be `PartialEq + Clone`; structs with other
+    // field types still derive and only lose the `diff` method.
+    let diff_bounds = fields_data.iter().map(|f| {
+        deferred_bound(
+            &f.field_ty,
+            ::quote::quote!(#core::cmp::PartialEq + #core::clone::Clone),
+        )
+    });
+
+    let serialize = if cfg!(feature = "serde") {
+        let serialize_bounds = fields_data.iter().map(|f| {
+            deferred_bound(&f.ty, ::quote::quote!(::derive_builder::__serde::Serialize))
+        });
+        let mut serialize_generics = builder_generics.clone();
+        serialize_generics
+            .make_where_clause()
+            .predicates
+            .extend(serialize_bounds);
+        let serialize_where_clause = &serialize_generics.where_clause;
+        // Only the fields that were set are written, so that a partially
+        // configured builder is saved with just the values given so far.
+        // Builders with fields that are not `Serialize` do not implement it.
+        ::quote::quote! {
+            #[automatically_derived]
+            impl #impl_generics ::derive_builder::__serde::Serialize for #builder_name #ty_generics #serialize_where_clause {
+                fn serialize<__S>(&self, serializer: __S) -> #core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: ::derive_builder::__serde::Serializer,
+                {
+                    use ::derive_builder::__serde::ser::SerializeStruct;
+                    let len = 0 #(+ self.#fields_names.is_some() as usize)*;
+                    let mut state = serializer.serialize_struct(stringify!(#builder_name), len)?;
+                    #(match &self.#fields_names {
+                        #core::option::Option::Some(value) => {
+                            state.serialize_field(stringify!(#fields_names), value)?
+                        },
+                        #core::option::Option::None => state.skip_field(stringify!(#fields_names))?,
+                    })*
+                    state.end()
+                }
+            }
+        }
+    } else {
+        ::proc_macro2::TokenStream::new()
+    };
+
+    let expanded = ::quote::quote! {
+        #errors
+        #[must_use = "a builder does nothing until it is built"]
+        #[allow(missing_docs)]
+        pub struct #builder_name #generics #where_clause {
+            #(#fields_names: #core::option::Option<#fields_types>,)*
+            __phantom: #core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #name #ty_generics #builder_where_clause {
+            pub fn builder() -> #builder_name #ty_generics {
+                #builder_name {
+                    #(#builder_fields,)*
+                    __phantom: #core::marker::PhantomData,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #builder_name #ty_generics #builder_where_clause {
+            #(#setters
+
+            )*
+
+            #(#closure_setters)*
+
+            #[must_use = "building has no side effects"]
+            pub fn build(&self) -> #core::result::Result<#name #ty_generics, #error_ty>
+            where
+                #(#clone_bounds),*
+            {
+                #core::result::Result::Ok(#name {
+                    #(#required_fields: #core::clone::Clone::clone(self.#required_fields.as_ref().ok_or_else(|| ::derive_builder::UninitializedFieldError::new(stringify!(#required_fields)))?),)*
+                    #(#optional_fields: #core::clone::Clone::clone(&self.#optional_fields),)*
+                    #(#repeated_names: #repeated_clones,)*
+                    #(#skipped_names: #skipped_values,)*
+                })
+            }
+
+            pub fn build_owned(self) -> #core::result::Result<#name #ty_generics, #error_ty> {
+                #core::result::Result::Ok(#name {
+                    #(#required_fields: self.#required_fields.ok_or_else(|| ::derive_builder::UninitializedFieldError::new(stringify!(#required_fields)))?,)*
+                    #(#optional_fields: self.#optional_fields,)*
+                    #(#repeated_names: #repeated_values,)*
+                    #(#skipped_names: #skipped_values,)*
+                })
+            }
+
+            pub fn take(&mut self) -> #core::result::Result<#name #ty_generics, #error_ty> {
+                #(if self.#required_fields.is_none() {
+                    return #core::result::Result::Err(::derive_builder::UninitializedFieldError::new(stringify!(#required_fields)).into());
+                })*
+                #core::mem::replace(self, <#name #ty_generics>::builder()).build_owned()
+            }
+
+            pub async fn build_async(self) -> #core::result::Result<#name #ty_generics, #error_ty> {
+                #core::result::Result::Ok(#name {
+                    #(#async_fields,)*
+                    #(#skipped_names: #skipped_values,)*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #core::clone::Clone for #builder_name #ty_generics #clone_where_clause {
+            fn clone(&self) -> Self {
+                #builder_name {
+                    #(#fields_names: #core::clone::Clone::clone(&self.#fields_names),)*
+                    __phantom: #core::marker::PhantomData,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::derive_builder::Buildable for #name #ty_generics #builder_where_clause {
+            type Builder = #builder_name #ty_generics;
+
+            fn builder() -> #builder_name #ty_generics {
+                <#name #ty_generics>::builder()
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::derive_builder::Builder for #builder_name #ty_generics #builder_where_clause {
+            type Target = #name #ty_generics;
+            type Error = #error_ty;
+
+            fn build_owned(self) -> #core::result::Result<#name #ty_generics, Self::Error> {
+                #builder_name::build_owned(self)
+            }
+        }
+
+        #[allow(missing_docs)]
+        pub struct #patch_name #generics #where_clause {
+            #(#patch_fields,)*
+            __phantom: #core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #patch_name #ty_generics #where_clause {
+            pub fn is_empty(&self) -> bool {
+                true #(&& self.#fields_names.is_none())*
+            }
+
+            pub fn changed_fields(&self) -> #alloc::vec::Vec<&'static str> {
+                let mut changed = #alloc::vec::Vec::new();
+                #(if self.#fields_names.is_some() {
+                    changed.push(stringify!(#fields_names));
+                })*
+                changed
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #core::default::Default for #patch_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #patch_name {
+                    #(#fields_names: #core::option::Option::None,)*
+                    __phantom: #core::marker::PhantomData,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        #[allow(missing_docs)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn diff(&self, other: &Self) -> #patch_name #ty_generics
+            where
+                #(#diff_bounds),*
+            {
+                #patch_name {
+                    #(#diffs,)*
+                    __phantom: #core::marker::PhantomData,
+                }
+            }
+
+            pub fn apply(&mut self, patch: #patch_name #ty_generics) {
+                #(#applies)*
+            }
+        }
+
+        #serialize
+    };
+
+    ::proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(Getters, attributes(builder, getter))]
+pub fn derive_getters(input: proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+    let input = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
+    ::proc_macro::TokenStream::from(accessors::expand_getters(&input))
+}
+
+#[proc_macro_derive(Setters, attributes(builder))]
+pub fn derive_setters(input: proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+    let input = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
+    ::proc_macro::TokenStream::from(accessors::expand_setters(&input))
+}
+
+/// The builder's setters and the accessors are named after the fields, so
+/// only structs with named fields can derive them.
+fn named_fields(
+    input: &::syn::DeriveInput,
+    derive: &str,
+) -> ::std::result::Result<
+    ::syn::punctuated::Punctuated<::syn::Field, ::syn::token::Comma>,
+    ::syn::Error,
+> {
+    match &input.data {
+        ::syn::Data::Struct(::syn::DataStruct {
+            fields: ::syn::Fields::Named(fields),
+            ..
+        }) => Ok(fields.named.clone()),
+        _ => Err(::syn::Error::new(
+            input.ident.span(),
+            format!("`derive({})` requires a struct with named fields", derive),
+        )),
+    }
+}
+
+fn process_fields(
+    fields: ::syn::punctuated::Punctuated<::syn::Field, ::syn::token::Comma>,
+    roots: &Roots,
+) -> ::std::vec::Vec<(FieldData, ::std::option::Option<::syn::Error>)> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone();
+            let (attrs, mut error) = builder_attrs(&field.attrs);
+            // `optional` marks a field whose `Option` is hidden behind an
+            // alias. Its element type is then named through `IntoIterator`,
+            // which `Option<T>` implements with `Item = T`.
+            let optional_ty = optional_type(&field.ty).or_else(|| {
+                let core = &roots.core;
+                let field_ty = &field.ty;
+                attrs.optional.then(|| {
+                    ::syn::parse_quote!(<#field_ty as #core::iter::IntoIterator>::Item)
+                })
+            });
+            let ty = if let Some(t) = optional_ty.clone() {
+                t
+            } else {
+                field.ty.clone()
+            };
+            if attrs.skip.is_some() {
+                if let Some(each) = &attrs.each {
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(each.span(), "`each` cannot be combined with `skip`"),
+                    );
+                }
+                if let Some(setter) = attrs.setter {
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(setter, "`setter` cannot be combined with `skip`"),
+                    );
+                }
+            }
+            let custom_collection = attrs.item.is_some();
+            let (repeat, each_error) = attr_each(ident.clone(), &ty, attrs.each, attrs.item);
+            if let Some(e) = each_error {
+                combine_error(&mut error, e);
+            }
+            let wrapped_ty = match &repeat {
+                Repeatable::No => &ty,
+                Repeatable::SingularOnly(_, t) | Repeatable::SingularAndPlural(_, t) => t,
+            };
+            let wrap = if attrs.wrap {
+                let wrap = pointer_type(wrapped_ty, roots);
+                if wrap.is_none() {
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(
+                            wrapped_ty.span(),
+                            "`setter(wrap)` expects a `Box`, `Arc` or `Rc` type",
+                        ),
+                    );
+                }
+                wrap
+            } else {
+                cow_type(wrapped_ty, roots)
+            };
+
+            if let Some(default_with) = &attrs.default_with {
+                if attrs.skip.is_some() {
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(
+                            default_with.span(),
+                            "`default_with` cannot be combined with `skip`",
+                        ),
+                    );
+                } else if !matches!(repeat, Repeatable::No) {
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(
+                            default_with.span(),
+                            "`default_with` cannot be used on `each` fields, which start out empty",
+                        ),
+                    );
+                } else if roots.no_std {
+                    // `build_async` converts the initializer's error into the
+                    // builder's error type, which under `no_std` can only
+                    // report a missing field.
+                    combine_error(
+                        &mut error,
+                        ::syn::Error::new(
+                            default_with.span(),
+                            "`default_with` is not supported with `builder(no_std)`, \
+                             whose error type cannot carry initializer failures",
+                        ),
+                    );
+                }
+            }
+
+            let optional = optional_ty.is_some();
+            if let (Some(strip), false) = (&attrs.strip_option, optional) {
+                combine_error(
+                    &mut error,
+                    ::syn::Error::new(
+                        strip.span(),
+                        "`setter(strip_option)` only applies to `Option` fields",
+                    ),
+                );
+            }
+            (
+                FieldData {
+                    ident: ident.clone(),
+                    ty,
+                    field_ty: field.ty.clone(),
+                    repeat,
+                    optional,
+                    strip_option: attrs.strip_option.is_none_or(|strip| strip.value),
+                    skip: attrs.skip.map(|skip| {
+                        let core = &roots.core;
+                        skip.unwrap_or_else(
+                            || ::syn::parse_quote!(#core::default::Default::default()),
+                        )
+                    }),
+                    wrap,
+                    // An initializer that cannot be used is dropped, so that
+                    // `build_async` adds no errors of its own.
+                    default_with: attrs.default_with.filter(|_| !roots.no_std),
+                    custom_collection,
+                },
+                error,
+            )
+        })
+        .collect()
+}
+
+/// Recognises `Option<T>` written either bare or through its full
+/// `std::option::Option` / `core::option::Option` path, with or without a
+/// leading `::`.
+fn optional_type(ty: &::syn::Type) -> ::std::option::Option<::syn::Type> {
+    type_argument(ty, "Option", &[&["std", "option"], &["core", "option"]])
+}
+
+/// Recognises `Box<T>`, `Arc<T>` and `Rc<T>` the same way `optional_type`
+/// recognises `Option<T>`.
+fn pointer_type(ty: &::syn::Type, roots: &Roots) -> ::std::option::Option<Wrapper> {
+    let alloc = &roots.alloc;
+    let (constructor, inner) = if let Some(inner) =
+        type_argument(ty, "Box", &[&["std", "boxed"], &["alloc", "boxed"]])
+    {
+        (::quote::quote!(#alloc::boxed::Box::new), inner)
+    } else if let Some(inner) =
+        type_argument(ty, "Arc", &[&["std", "sync"], &["alloc", "sync"]])
+    {
+        (::quote::quote!(#alloc::sync::Arc::new), inner)
+    } else if let Some(inner) =
+        type_argument(ty, "Rc", &[&["std", "rc"], &["alloc", "rc"]])
+    {
+        (::quote::quote!(#alloc::rc::Rc::new), inner)
+    } else {
+        return None;
+    };
+
+    // A trait object cannot be passed by value, so the setter accepts any
+    // implementor instead and lets the allocation coerce it.
+    let param_ty = match &inner {
+        ::syn::Type::TraitObject(::syn::TypeTraitObject { bounds, .. }) => {
+            if bounds
+                .iter()
+                .any(|b| matches!(b, ::syn::TypeParamBound::Lifetime(_)))
+            {
+                ::quote::quote!(impl #bounds)
+            } else {
+                ::quote::quote!(impl #bounds + 'static)
+            }
+        },
+        _ => ::quote::quote!(#inner),
+    };
+    Some(Wrapper {
+        constructor,
+        param_ty,
+    })
+}
+
+/// Recognises `Cow<'a, T>`, whose setters accept anything convertible into it.
+fn cow_type(ty: &::syn::Type, roots: &Roots) -> ::std::option::Option<Wrapper> {
+    let core = &roots.core;
+    let ::syn::Type::Path(::syn::TypePath {
+        qself: None,
+        path: ::syn::Path { segments, .. },
+    }) = ty
+    else {
+        return None;
+    };
+    let prefix: ::std::vec::Vec<::std::string::String> = segments
+        .iter()
+        .take(segments.len() - 1)
+        .map(|s| s.ident.to_string())
+        .collect();
+    if !(prefix.is_empty()
+        || prefix == ["std", "borrow"]
+        || prefix == ["alloc", "borrow"])
+    {
+        return None;
+    }
+    if segments.last()?.ident != "Cow" {
+        return None;
+    }
+    Some(Wrapper {
+        constructor: ::quote::quote!(#core::convert::Into::into),
+        param_ty: ::quote::quote!(impl #core::convert::Into<#ty>),
+    })
+}
+
+/// Returns the single type argument of `ty` if it is a path to `name`, written
+/// bare or prefixed by one of `modules`.
+fn type_argument(
+    ty: &::syn::Type,
+    name: &str,
+    modules: &[&[&str]],
+) -> ::std::option::Option<::syn::Type> {
+    let segments = match ty {
+        ::syn::Type::Path(::syn::TypePath {
+            qself: None,
+            path: ::syn::Path { segments, .. },
+        }) => segments,
+        _ => return None,
+    };
+
+    let prefix: ::std::vec::Vec<::std::string::String> = segments
+        .iter()
+        .take(segments.len() - 1)
+        .map(|s| s.ident.to_string())
+        .collect();
+    if !(prefix.is_empty() || modules.iter().any(|m| prefix == *m)) {
+        return None;
+    }
+
+    let args = match segments.last()? {
+        ::syn::PathSegment {
+            ident,
+            arguments:
+                ::syn::PathArguments::AngleBracketed(::syn::AngleBracketedGenericArguments {
+                    args,
+                    ..
+                }),
+        } if ident == name && args.len() == 1 => args,
+        _ => return None,
+    };
+
+    match &args[0] {
+        ::syn::GenericArgument::Type(t) => Some(t.clone()),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct BuilderAttrs {
+    each: ::std::option::Option<::syn::LitStr>,
+    optional: bool,
+    strip_option: ::std::option::Option<::syn::LitBool>,
+    /// Where `setter(...)` was given, to reject it on skipped fields.
+    setter: ::std::option::Option<::proc_macro2::Span>,
+    /// `Some(None)` for a bare `skip`, which falls back to `Default`.
+    skip: ::std::option::Option<::std::option::Option<::syn::Expr>>,
+    wrap: bool,
+    default_with: ::std::option::Option<::syn::Path>,
+    item: ::std::option::Option<::syn::Type>,
+}
+
+struct StructAttrs {
+    no_std: bool,
+}
+
+const STRUCT_ATTRS: &[&str] = &["no_std"];
+
+/// Parses the `#[builder(...)]` attributes on the struct itself, collecting
+/// errors the same way `builder_attrs` does for fields.
+fn struct_attrs(
+    attrs: &[::syn::Attribute],
+) -> (StructAttrs, ::std::option::Option<::syn::Error>) {
+    let mut struct_attrs = StructAttrs { no_std: false };
+    let mut errors: ::std::option::Option<::syn::Error> = None;
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("no_std") {
+                struct_attrs.no_std = true;
+            } else {
+                combine_error(
+                    &mut errors,
+                    unknown_attr(&meta.path, "builder", STRUCT_ATTRS),
+                );
+                skip_meta_value(&meta)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = parsed {
+            combine_error(&mut errors, e);
+        }
+    }
+    (struct_attrs, errors)
+}
+
+const BUILDER_ATTRS: &[&str] =
+    &["default_with", "each", "item", "optional", "setter", "skip"];
+const SETTER_ATTRS: &[&str] = &["strip_option", "wrap"];
+
+/// Parses every `#[builder(...)]` attribute of a field. Unknown keys do not
+/// stop parsing: their errors are collected and returned together with the
+/// attributes that were recognised, so that a best-effort builder can still
+/// be emitted.
+fn builder_attrs(
+    attrs: &[::syn::Attribute],
+) -> (BuilderAttrs, ::std::option::Option<::syn::Error>) {
+    let mut builder_attrs = BuilderAttrs::default();
+    let mut errors: ::std::option::Option<::syn::Error> = None;
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                let value = meta.value()?;
+                builder_attrs.each = Some(value.parse()?);
+            } else if meta.path.is_ident("setter") {
+                builder_attrs.setter = Some(meta.path.span());
+                meta.parse_nested_meta(|setter| {
+                    if setter.path.is_ident("strip_option") {
+                        let value = setter.value()?;
+                        builder_attrs.strip_option = Some(value.parse()?);
+                    } else if setter.path.is_ident("wrap") {
+                        builder_attrs.wrap = true;
+                    } else {
+                        combine_error(
+                            &mut errors,
+                            unknown_attr(&setter.path, "setter", SETTER_ATTRS),
+                        );
+                        skip_meta_value(&setter)?;
+                    }
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("optional") {
+                builder_attrs.optional = true;
+            } else if meta.path.is_ident("item") {
+                let value: ::syn::LitStr = meta.value()?.parse()?;
+                builder_attrs.item = Some(value.parse()?);
+            } else if meta.path.is_ident("default_with") {
+                let value = meta.value()?;
+                builder_attrs.default_with = Some(value.parse()?);
+            } else if meta.path.is_ident("skip") {
+                builder_attrs.skip = Some(if meta.input.peek(::syn::Token![=]) {
+                    Some(meta.value()?.parse()?)
+                } else {
+                    None
+                });
+            } else {
+                combine_error(
+                    &mut errors,
+                    unknown_attr(&meta.path, "builder", BUILDER_ATTRS),
+                );
+                skip_meta_value(&meta)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = parsed {
+            combine_error(&mut errors, e);
+        }
+    }
+    (builder_attrs, errors)
+}
+
+/// `ty: bound`, checked where the bounded item is used instead of where it
+/// is declared. A predicate that mentions no type parameter, such as
+/// `String: Copy`, is otherwise rejected on the spot and would fail the
+/// whole derive. rustc does not reject trivially false bounds that are
+/// higher-ranked, so one with an unused lifetime only removes the items that
+/// carry it for the field types that do not satisfy it.
+fn deferred_bound(
+    ty: &::syn::Type,
+    bound: ::proc_macro2::TokenStream,
+) -> ::syn::WherePredicate {
+    ::syn::parse_quote!(for<'__deferred> #ty: #bound)
+}
+
+fn combine_error(errors: &mut ::std::option::Option<::syn::Error>, error: ::syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
+    }
+}
+
+fn unknown_attr(path: &::syn::Path, kind: &str, expected: &[&str]) -> ::syn::Error {
+    let key = ::quote::ToTokens::to_token_stream(path).to_string();
+    let suggestion = expected
+        .iter()
+        .map(|candidate| (edit_distance(&key, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= candidate.len() / 3 + 1)
+        .min_by_key(|(distance, _)| *distance);
+    let message = match suggestion {
+        Some((_, candidate)) => {
+            format!(
+                "unknown {} attribute `{}`, did you mean `{}`?",
+                kind, key, candidate
+            )
+        },
+        None => format!(
+            "unknown {} attribute `{}`, expected one of {}",
+            kind,
+            key,
+            expected
+                .iter()
+                .map(|e| format!("`{}`", e))
+                .collect::<::std::vec::Vec<_>>()
+                .join(", ")
+        ),
+    };
+    ::syn::Error::new(path.span(), message)
+}
+
+/// Consumes the `= value` or `(...)` following an unrecognised key so that
+/// the remaining keys of the attribute can still be parsed.
+fn skip_meta_value(meta: &::syn::meta::ParseNestedMeta) -> ::syn::Result<()> {
+    if meta.input.peek(::syn::Token![=]) {
+        meta.value()?.parse::<::syn::Expr>()?;
+    } else if meta.input.peek(::syn::token::Paren) {
+        meta.input.parse::<::proc_macro2::TokenTree>()?;
+    }
+    Ok(())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: ::std::vec::Vec<char> = b.chars().collect();
+    let mut row: ::std::vec::Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Resolves `each` to the singular setter name and the element type, which is
+/// either the argument of a `Vec<T>` field or given explicitly with `item`.
+fn attr_each(
+    ident: ::std::option::Option<::proc_macro2::Ident>,
+    ty: &::syn::Type,
+    each: ::std::option::Option<::syn::LitStr>,
+    item: ::std::option::Option<::syn::Type>,
+) -> (Repeatable, ::std::option::Option<::syn::Error>) {
+    let Some(each) = each else {
+        let error =
+            item.map(|item| ::syn::Error::new(item.span(), "`item` requires `each`"));
+        return (Repeatable::No, error);
+    };
+    let Some(inner_ty) =
+        item.or_else(|| type_argument(ty, "Vec", &[&["std", "vec"], &["alloc", "vec"]]))
+    else {
+        let error = ::syn::Error::new(
+            ty.span(),
+            "`each` expects a `Vec<T>` field, or the element type of another collection given with `item = \"T\"`",
+        );
+        return (Repeatable::No, Some(error));
+    };
+    let singular = ::syn::Ident::new(&each.value(), each.span());
+    if ident.as_ref().is_some_and(|i| *i == singular) {
+        return (Repeatable::SingularOnly(singular, inner_ty), None);
+    }
+    (Repeatable::SingularAndPlural(singular, inner_ty), None)
+}
@@ -0,0 +1,50 @@
+// Alongside the builder, generate a `CommandPatch` struct holding one optional
+// value per field. `Command::diff` compares two values and records in the patch
+// only the fields that changed, taking their value from `other`, while
+// `Command::apply` writes every field present in a patch back into a `Command`.
+//
+// Optional fields keep their full `Option<T>` type inside the patch so that a
+// change from `Some(..)` back to `None` can be represented.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let mut old = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    let new = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .env(vec![])
+        .build()
+        .unwrap();
+
+    let patch = old.diff(&new);
+    assert!(!patch.is_empty());
+    assert_eq!(patch.changed_fields(), vec!["args", "current_dir"]);
+    assert!(patch.executable.is_none());
+    assert_eq!(patch.current_dir, Some(None));
+
+    old.apply(patch);
+    assert_eq!(old.args, vec!["build", "--release"]);
+    assert!(old.current_dir.is_none());
+    assert!(old.diff(&new).is_empty());
+
+    let empty = CommandPatch::default();
+    assert!(empty.changed_fields().is_empty());
+}
@@ -0,0 +1,42 @@
+// Every type deriving Builder also implements the `Buildable` trait from the
+// derive_builder crate, and its generated builder implements `Builder`. This
+// lets generic code such as config loaders or test fixtures obtain a builder
+// and build a value without naming the concrete builder type.
+
+use derive_builder::{Buildable, Builder};
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn build_default<T: Buildable>() -> Result<T, <T::Builder as Builder>::Error> {
+    T::builder().build_owned()
+}
+
+fn configure<T, F>(f: F) -> T
+where
+    T: Buildable,
+    <T::Builder as Builder>::Error: std::fmt::Debug,
+    F: FnOnce(&mut T::Builder),
+{
+    let mut builder = T::builder();
+    f(&mut builder);
+    builder.build_owned().unwrap()
+}
+
+fn main() {
+    assert!(build_default::<Command>().is_err());
+
+    let command: Command = configure(|builder: &mut CommandBuilder| {
+        builder
+            .executable("cargo".to_owned())
+            .arg("build".to_owned());
+    });
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.current_dir.is_none());
+}
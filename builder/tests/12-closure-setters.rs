@@ -0,0 +1,45 @@
+// Fields with an `each` attribute also get an `<field>_with` method which
+// hands a closure mutable access to the vector collected so far, and Option
+// fields get an `<field>_map` method which replaces the current value with the
+// result of a closure. Together they allow removing or reordering entries, and
+// resetting an optional field back to None.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .arg("build".to_owned())
+        .args_with(|args| args.reverse())
+        .env("RUST_LOG=debug".to_owned())
+        .env("RUST_BACKTRACE=1".to_owned())
+        .env_with(|env| env.retain(|var| !var.starts_with("RUST_LOG")))
+        .current_dir("..".to_owned())
+        .current_dir_map(|dir| dir.map(|dir| format!("{}/target", dir)))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.env, vec!["RUST_BACKTRACE=1"]);
+    assert_eq!(command.current_dir.as_deref(), Some("../target"));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .current_dir_map(|_| None)
+        .build()
+        .unwrap();
+
+    assert!(command.current_dir.is_none());
+}
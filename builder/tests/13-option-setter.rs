@@ -0,0 +1,55 @@
+// By default the setter of an `Option<T>` field takes a `T`, which leaves no
+// way to pass an `Option` through or to set the field back to `None`. With
+// `#[builder(setter(strip_option = false))]` the setter takes the `Option<T>`
+// unchanged.
+//
+// Optional fields are also recognised when their type is spelled through the
+// full `std::option::Option` or `core::option::Option` path. An `Option`
+// hidden behind a type alias cannot be seen by the macro, so such fields are
+// marked with `#[builder(optional)]`.
+
+use derive_builder::Builder;
+
+type Maybe<T> = Option<T>;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    current_dir: std::option::Option<String>,
+    #[builder(setter(strip_option = false))]
+    user: Option<String>,
+    #[builder(setter(strip_option = false))]
+    group: ::core::option::Option<u32>,
+    #[builder(optional)]
+    shell: Maybe<String>,
+    #[builder(optional, setter(strip_option = false))]
+    umask: Maybe<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.current_dir.is_none());
+    assert!(command.user.is_none());
+    assert!(command.group.is_none());
+    assert!(command.shell.is_none());
+    assert!(command.umask.is_none());
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .user(Some("root".to_owned()))
+        .user(None)
+        .group(Some(0))
+        .shell("sh".to_owned())
+        .umask(Some(0o022))
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert!(command.user.is_none());
+    assert_eq!(command.group, Some(0));
+    assert_eq!(command.shell.as_deref(), Some("sh"));
+    assert_eq!(command.umask, Some(0o022));
+}
@@ -0,0 +1,50 @@
+// The borrowing `build` clones every field exactly once, which needs the field
+// types to be `Clone`. Two more ways of finishing a builder move the fields out
+// instead and have no such requirement:
+//
+//   - `build_owned(self)` consumes the builder;
+//   - `take(&mut self)` can end a method chain and resets the builder to its
+//     initial state. If a required field is missing the builder is left as it
+//     was.
+
+use derive_builder::Builder;
+
+pub struct Connection {
+    id: u32,
+}
+
+#[derive(Builder)]
+pub struct Client {
+    name: String,
+    connection: Connection,
+    #[builder(each = "payload")]
+    payloads: Vec<Vec<u8>>,
+    fallback: Option<Connection>,
+}
+
+fn main() {
+    let mut builder = Client::builder();
+    builder
+        .name("client".to_owned())
+        .connection(Connection { id: 1 })
+        .payload(vec![0; 1024]);
+    let client = builder.build_owned().unwrap();
+    assert_eq!(client.name, "client");
+    assert_eq!(client.connection.id, 1);
+    assert_eq!(client.payloads.len(), 1);
+    assert!(client.fallback.is_none());
+
+    let mut builder = Client::builder();
+    builder.name("client".to_owned());
+    assert!(builder.take().is_err());
+
+    let client = builder
+        .connection(Connection { id: 2 })
+        .fallback(Connection { id: 3 })
+        .take()
+        .unwrap();
+    assert_eq!(client.name, "client");
+    assert_eq!(client.connection.id, 2);
+    assert_eq!(client.fallback.map(|c| c.id), Some(3));
+    assert!(builder.take().is_err());
+}
@@ -0,0 +1,64 @@
+// Every problem in the builder attributes of a struct is reported at once
+// rather than stopping at the first one, and a misspelled key comes with a
+// suggestion for the closest valid key.
+//
+// The builder itself is still generated on a best-effort basis, so the setters
+// used in main below do not cause additional errors while the attributes are
+// being fixed.
+//
+// `setter(strip_option)` is rejected on fields that are not `Option`s, where
+// there is nothing to strip.
+//
+// A skipped field has no setter and is never set, so `each`, `setter(...)` and
+// `default_with` are rejected on it.
+//
+// `default_with` is rejected on `no_std` builders, whose error type can only
+// report a missing field and not the error of a failed initializer.
+
+extern crate alloc;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(eahc = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env", setter(strip_options = false))]
+    env: Vec<String>,
+    #[builder(required, setter(strip_option = false))]
+    current_dir: Option<String>,
+    #[builder(setter(strip_option = true))]
+    jobs: u32,
+}
+
+#[derive(Builder)]
+pub struct Cache {
+    #[builder(skip, each = "entry")]
+    entries: Vec<String>,
+    #[builder(skip, setter(strip_option = false))]
+    evicted: Option<String>,
+    #[builder(skip, default_with = default_timeout)]
+    ttl: u64,
+}
+
+async fn default_timeout() -> Result<u64, std::io::Error> {
+    Ok(30)
+}
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Connection {
+    #[builder(default_with = default_timeout)]
+    timeout: u64,
+}
+
+fn main() {
+    let _ = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec![])
+        .env("RUST_LOG=debug".to_owned())
+        .current_dir(None)
+        .jobs(4)
+        .build();
+}
@@ -0,0 +1,60 @@
+// A field marked `#[builder(skip)]` gets no setter and is not stored on the
+// builder. `build` fills it in from `Default::default()`, or from the given
+// expression when written as `#[builder(skip = expr)]`. The expression is
+// evaluated anew every time a value is built.
+//
+// Skipped fields are internal state, so they are left out of the generated
+// patch as well. A type parameter that only a skipped field mentions, such as
+// the one in a `PhantomData` marker, is still accepted by both.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+fn initial_capacity() -> usize {
+    16
+}
+
+#[derive(Builder)]
+pub struct Resolver {
+    nameserver: String,
+    #[builder(skip)]
+    cache: HashMap<String, String>,
+    #[builder(skip)]
+    lookups: u64,
+    #[builder(skip = initial_capacity() * 2)]
+    capacity: usize,
+}
+
+#[derive(Builder)]
+pub struct Handle<T> {
+    id: u32,
+    #[builder(skip)]
+    _marker: PhantomData<T>,
+}
+
+fn main() {
+    let mut resolver = Resolver::builder()
+        .nameserver("1.1.1.1".to_owned())
+        .build()
+        .unwrap();
+    assert!(resolver.cache.is_empty());
+    assert_eq!(resolver.lookups, 0);
+    assert_eq!(resolver.capacity, 32);
+
+    resolver.lookups += 1;
+    let other = Resolver::builder()
+        .nameserver("8.8.8.8".to_owned())
+        .take()
+        .unwrap();
+    let patch = resolver.diff(&other);
+    assert_eq!(patch.changed_fields(), vec!["nameserver"]);
+    resolver.apply(patch);
+    assert_eq!(resolver.nameserver, "8.8.8.8");
+    assert_eq!(resolver.lookups, 1);
+
+    let handle: Handle<String> = Handle::builder().id(7).build().unwrap();
+    assert_eq!(handle.id, 7);
+    let patch = handle.diff(&Handle::builder().id(8).build().unwrap());
+    assert_eq!(patch.changed_fields(), vec!["id"]);
+}
@@ -0,0 +1,80 @@
+// With `#[builder(setter(wrap))]` the setter of a `Box<T>`, `Arc<T>` or `Rc<T>`
+// field takes the `T` and allocates the pointer itself. When `T` is a trait
+// object the setter accepts any type implementing the trait.
+//
+// The pointer may also sit inside an `Option`, or be the element type of a
+// vector whose singular setter is generated through `each`. Combined with
+// `setter(strip_option = false)` the setter takes the field's own `Option`
+// unconverted, so it can be cleared with a plain `None`.
+
+use derive_builder::Builder;
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub trait Handler {
+    fn handle(&self, request: &str) -> String;
+}
+
+pub struct Echo;
+
+impl Handler for Echo {
+    fn handle(&self, request: &str) -> String {
+        request.to_owned()
+    }
+}
+
+pub struct Upper;
+
+impl Handler for Upper {
+    fn handle(&self, request: &str) -> String {
+        request.to_uppercase()
+    }
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(setter(wrap))]
+    name: Rc<String>,
+    #[builder(setter(wrap))]
+    handler: Arc<dyn Handler + Send + Sync>,
+    #[builder(setter(wrap))]
+    fallback: Option<std::sync::Arc<dyn Handler + Send + Sync>>,
+    #[builder(setter(wrap, strip_option = false))]
+    hook: Option<Box<dyn Handler>>,
+    #[builder(each = "middleware", setter(wrap))]
+    middlewares: Vec<Box<dyn Handler>>,
+    unwrapped: Option<Box<u32>>,
+}
+
+fn main() {
+    let server = Server::builder()
+        .name("server".to_owned())
+        .handler(Echo)
+        .fallback(Upper)
+        .hook(None)
+        .middleware(Upper)
+        .middleware(Echo)
+        .unwrapped(Box::new(1))
+        .take()
+        .unwrap();
+
+    assert_eq!(*server.name, "server");
+    assert_eq!(server.handler.handle("ping"), "ping");
+    assert_eq!(server.fallback.unwrap().handle("ping"), "PING");
+    assert!(server.hook.is_none());
+    let responses: Vec<String> = server
+        .middlewares
+        .iter()
+        .map(|m| m.handle("ping"))
+        .collect();
+    assert_eq!(responses, vec!["PING", "ping"]);
+    assert_eq!(server.unwrapped.as_deref(), Some(&1));
+
+    let server = Server::builder()
+        .name("hooked".to_owned())
+        .handler(Echo)
+        .hook(Some(Box::new(Upper)))
+        .take()
+        .unwrap();
+    assert_eq!(server.hook.unwrap().handle("ping"), "PING");
+}
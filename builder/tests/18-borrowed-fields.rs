@@ -0,0 +1,86 @@
+// Structs with lifetime or type parameters get a builder with the same
+// parameters, so fields may borrow from the caller: `&'a str`, `&'a [T]` and
+// `Cow<'a, str>` all work. The setter of a `Cow` field accepts anything
+// convertible into it, so both borrowed and owned strings can be passed. With
+// `setter(strip_option = false)` an optional `Cow` is passed as the field's own
+// `Option`, so `None` needs no annotation.
+//
+// Unsized values behind a `Box<dyn Trait>` cannot be cloned, so such builders
+// are finished with `build_owned` or `take` instead of `build`.
+
+use derive_builder::Builder;
+use std::borrow::Cow;
+
+pub trait Log {
+    fn log(&self, line: &str) -> String;
+}
+
+pub struct Prefixed(&'static str);
+
+impl Log for Prefixed {
+    fn log(&self, line: &str) -> String {
+        format!("{}{}", self.0, line)
+    }
+}
+
+#[derive(Builder)]
+pub struct Request<'a> {
+    method: &'a str,
+    path: Cow<'a, str>,
+    body: &'a [u8],
+    #[builder(each = "header")]
+    headers: Vec<Cow<'a, str>>,
+    comment: Option<Cow<'a, str>>,
+    #[builder(setter(strip_option = false))]
+    referrer: Option<Cow<'a, str>>,
+    logger: Box<dyn Log>,
+}
+
+#[derive(Builder)]
+pub struct Page<'a, T> {
+    items: &'a [T],
+    number: usize,
+}
+
+fn main() {
+    let body = vec![1, 2, 3];
+    let path = String::from("/index.html");
+    let request = Request::builder()
+        .method("GET")
+        .path(&path[..])
+        .body(&body)
+        .header("Accept: */*")
+        .header(format!("Content-Length: {}", body.len()))
+        .comment(String::from("owned"))
+        .referrer(None)
+        .logger(Box::new(Prefixed("> ")))
+        .take()
+        .unwrap();
+
+    assert_eq!(request.method, "GET");
+    assert!(matches!(request.path, Cow::Borrowed("/index.html")));
+    assert_eq!(request.body, [1, 2, 3]);
+    assert_eq!(request.headers, vec!["Accept: */*", "Content-Length: 3"]);
+    assert!(matches!(request.comment, Some(Cow::Owned(_))));
+    assert_eq!(request.referrer, None);
+    assert_eq!(request.logger.log(request.method), "> GET");
+
+    let request = Request::builder()
+        .method("HEAD")
+        .path("/")
+        .body(&[])
+        .referrer(Some(Cow::Borrowed("/index.html")))
+        .logger(Box::new(Prefixed("")))
+        .take()
+        .unwrap();
+    assert_eq!(request.referrer.as_deref(), Some("/index.html"));
+
+    let items = ["a", "b", "c"];
+    let page = Page::builder()
+        .items(&items[1..])
+        .number(2)
+        .build()
+        .unwrap();
+    assert_eq!(page.items, ["b", "c"]);
+    assert_eq!(page.number, 2);
+}
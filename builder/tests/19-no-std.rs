@@ -0,0 +1,54 @@
+// With `#[builder(no_std)]` on the struct, the generated code refers only to
+// `::core` and `::alloc`, so it can be used from `no_std` crates that have an
+// allocator. The caller needs `extern crate alloc;` in scope. As `dyn Error`
+// cannot be boxed there, `build` returns `derive_builder::UninitializedFieldError`
+// directly, which implements `core::fmt::Display`.
+//
+// This test binary still links std for its runtime, but under a different name
+// so that any leftover `::std` path in the expansion fails to resolve.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use derive_builder::{Builder, UninitializedFieldError};
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(setter(wrap))]
+    stdin: Box<[u8; 4]>,
+    #[builder(skip)]
+    pid: u32,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .stdin([0; 4])
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.current_dir.is_none());
+    assert_eq!(command.stdin.len(), 4);
+    assert_eq!(command.pid, 0);
+
+    let error = Command::builder().build().err().unwrap();
+    assert_eq!(error, UninitializedFieldError::new("executable"));
+    assert_eq!(error.to_string(), "executable must be set before building");
+
+    let patch = command.diff(&command);
+    assert!(patch.is_empty());
+}
@@ -0,0 +1,64 @@
+// Structs that are built once and then read or edited field by field can
+// derive `Getters` and `Setters` next to `Builder`. They read the same
+// `#[builder(...)]` attributes, so a field is treated the same way everywhere.
+//
+// Getters:
+//
+//     fn name(&self) -> &String;
+//     fn name_mut(&mut self) -> &mut String;
+//     fn current_dir(&self) -> Option<&String>;   // `Option<T>` fields
+//     fn env(&self) -> &[String];                 // `each` fields
+//     fn pid(&self) -> u32;                       // `#[getter(copy)]`
+//
+// and nothing for `#[getter(skip)]`. Setters mirror the builder's setters,
+// prefixed with `set_`, plus `push_<each>` for repeated fields:
+//
+//     fn set_current_dir(&mut self, current_dir: String) -> &mut Self;
+//     fn push_env(&mut self, env: String) -> &mut Self;
+
+use derive_builder::{Builder, Getters, Setters};
+
+#[derive(Builder, Getters, Setters)]
+pub struct Process {
+    name: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[getter(copy)]
+    pid: u32,
+    #[getter(skip)]
+    #[builder(skip)]
+    restarts: u64,
+}
+
+fn main() {
+    let mut process = Process::builder()
+        .name("cargo".to_owned())
+        .arg("build".to_owned())
+        .env("RUST_LOG=debug".to_owned())
+        .pid(7)
+        .build()
+        .unwrap();
+
+    assert_eq!(process.name(), "cargo");
+    assert_eq!(process.args(), ["build"]);
+    assert_eq!(process.env(), ["RUST_LOG=debug"]);
+    assert_eq!(process.current_dir(), None);
+    assert_eq!(process.pid(), 7);
+    assert_eq!(process.restarts, 0);
+
+    process.name_mut().push_str("-nightly");
+    process
+        .set_current_dir("/tmp".to_owned())
+        .push_arg("--release".to_owned())
+        .set_env(Vec::new())
+        .set_pid(8);
+
+    assert_eq!(process.name(), "cargo-nightly");
+    assert_eq!(process.args(), ["build", "--release"]);
+    assert!(process.env().is_empty());
+    assert_eq!(process.current_dir().map(String::as_str), Some("/tmp"));
+    assert_eq!(process.pid(), 8);
+}
@@ -0,0 +1,112 @@
+// Some fields are expensive to produce and should only be produced when the
+// caller did not supply one, such as a connection pool. Such a field can name
+// an async initializer with `#[builder(default_with = path::to::fn)]`:
+//
+//     async fn connect() -> Result<Pool, E>
+//
+// where `E` converts into the builder's error type. For an `Option<T>` field
+// the initializer returns `T`.
+//
+// `build_async(self)` consumes the builder and awaits the initializer of
+// every such field that was left unset, in declaration order. Its future
+// does not depend on any particular runtime. The synchronous `build` methods
+// are unchanged and still report these fields as missing.
+
+use derive_builder::Builder;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq)]
+pub struct Pool {
+    size: usize,
+}
+
+mod pool {
+    use super::{Pool, CONNECTIONS};
+    use std::sync::atomic::Ordering;
+
+    pub async fn connect() -> Result<Pool, std::io::Error> {
+        CONNECTIONS.fetch_add(1, Ordering::SeqCst);
+        Ok(Pool { size: 4 })
+    }
+}
+
+async fn refuse() -> Result<String, std::io::Error> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::ConnectionRefused,
+        "no replica available",
+    ))
+}
+
+async fn region() -> Result<String, std::fmt::Error> {
+    Ok("eu-west-1".to_owned())
+}
+
+#[derive(Builder)]
+pub struct Service {
+    name: String,
+    #[builder(default_with = pool::connect)]
+    pool: Pool,
+    #[builder(default_with = region)]
+    region: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Replica {
+    #[builder(default_with = refuse)]
+    address: String,
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let mut builder = Service::builder();
+    builder.name("api".to_owned());
+    let service = block_on(builder.build_async()).unwrap();
+    assert_eq!(service.name, "api");
+    assert_eq!(service.pool, Pool { size: 4 });
+    assert_eq!(service.region.as_deref(), Some("eu-west-1"));
+    assert_eq!(CONNECTIONS.load(Ordering::SeqCst), 1);
+
+    let mut builder = Service::builder();
+    builder
+        .name("worker".to_owned())
+        .pool(Pool { size: 1 })
+        .region("us-east-2".to_owned());
+    let service = block_on(builder.build_async()).unwrap();
+    assert_eq!(service.pool, Pool { size: 1 });
+    assert_eq!(service.region.as_deref(), Some("us-east-2"));
+    assert_eq!(CONNECTIONS.load(Ordering::SeqCst), 1);
+
+    let mut builder = Service::builder();
+    builder.pool(Pool { size: 2 });
+    let err = block_on(builder.build_async()).err().unwrap();
+    assert_eq!(err.to_string(), "name must be set before building");
+
+    let err = block_on(Replica::builder().build_async()).err().unwrap();
+    assert_eq!(err.to_string(), "no replica available");
+
+    let mut builder = Service::builder();
+    builder.name("api".to_owned());
+    assert!(builder.take().is_err());
+}
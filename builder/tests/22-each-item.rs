@@ -0,0 +1,51 @@
+// `each` finds the element type by looking at the field's type, which only
+// works when it is written out as `Vec<T>`. For a type parameter, an alias or
+// another collection, name the element type with `item`:
+//
+//     #[builder(each = "stage", item = "T")]
+//     stages: C,
+//
+// The collection then only needs to implement `Default` and `Extend<T>`. The
+// builder requires these bounds where it is used, so a struct does not have
+// to declare them on its own type parameters.
+
+use derive_builder::{Builder, Setters};
+use std::collections::{BTreeSet, VecDeque};
+
+type Args = Vec<String>;
+
+#[derive(Builder, Setters)]
+pub struct Pipeline<C, T> {
+    #[builder(each = "stage", item = "T")]
+    stages: C,
+    default_stage: T,
+    #[builder(each = "arg", item = "String")]
+    args: Args,
+    #[builder(each = "tag", item = "&'static str")]
+    tags: Option<BTreeSet<&'static str>>,
+    #[builder(each = "input", item = "u8")]
+    inputs: std::vec::Vec<u8>,
+}
+
+fn main() {
+    let mut pipeline = Pipeline::<VecDeque<u32>, u32>::builder()
+        .stage(1)
+        .stage(2)
+        .default_stage(0)
+        .arg("--verbose".to_owned())
+        .tag("nightly")
+        .tag("arm64")
+        .tag("nightly")
+        .input(0)
+        .build()
+        .unwrap();
+
+    assert_eq!(pipeline.stages, [1, 2]);
+    assert_eq!(pipeline.args, ["--verbose"]);
+    assert_eq!(pipeline.tags, Some(BTreeSet::from(["arm64", "nightly"])));
+    assert_eq!(pipeline.inputs, [0]);
+
+    pipeline.push_stage(3).push_tag("x86_64");
+    assert_eq!(pipeline.stages, [1, 2, 3]);
+    assert_eq!(pipeline.tags.map(|t| t.len()), Some(3));
+}
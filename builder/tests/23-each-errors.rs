@@ -0,0 +1,17 @@
+// Without `item`, `each` has to be able to see that the field is a `Vec`, and
+// `item` on its own has no setter to apply to. Both are reported instead of
+// being ignored.
+
+use derive_builder::Builder;
+
+type Args = Vec<String>;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Args,
+    #[builder(item = "String")]
+    env: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Crates that deny warnings should be able to derive builders. Generated items
+// are marked `#[automatically_derived]` and allow the lints that would
+// otherwise fire on them, such as `missing_docs` on the builder's methods.
+
+#![deny(warnings, missing_docs)]
+
+//! Documented crate.
+
+use derive_builder::{Builder, Getters, Setters};
+
+/// A documented struct.
+#[derive(Builder, Getters, Setters)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable(), "cargo");
+}
@@ -0,0 +1,20 @@
+// A builder does nothing until it is built, and `build` has no side effects.
+// Both are `#[must_use]` so that a builder or its result is not dropped by
+// accident.
+
+#![deny(unused_must_use)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {
+    Command::builder();
+
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    builder.build();
+}
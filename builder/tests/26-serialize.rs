@@ -0,0 +1,54 @@
+// With the `serde` feature enabled, builders implement `serde::Serialize`.
+// Only the fields that were set are written, which makes it possible to save
+// a partially configured builder, for example to a config file. An `each`
+// field counts as set once it was given an element or a whole collection,
+// even an empty one.
+//
+// Builders whose fields are not all `Serialize` still derive, and only lack
+// the impl.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(skip)]
+    runs: u64,
+}
+
+pub struct Connection;
+
+#[derive(Builder)]
+pub struct Client {
+    name: String,
+    connection: Connection,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    assert_eq!(
+        serde_json::to_string(&builder).unwrap(),
+        r#"{"executable":"cargo"}"#
+    );
+    assert!(builder.clone().build().unwrap().args.is_empty());
+
+    builder.arg("build".to_owned()).current_dir("/tmp".to_owned());
+    assert_eq!(
+        serde_json::to_value(&builder).unwrap(),
+        serde_json::json!({
+            "executable": "cargo",
+            "args": ["build"],
+            "current_dir": "/tmp",
+        })
+    );
+
+    let mut empty = Command::builder();
+    empty.args(Vec::new());
+    assert_eq!(serde_json::to_string(&empty).unwrap(), r#"{"args":[]}"#);
+
+    let _ = Client::builder().name("client".to_owned()).take();
+}
@@ -0,0 +1,19 @@
+// Getters and setters are named after the fields they access, so `Getters`
+// and `Setters` can only be derived for structs with named fields. Tuple
+// structs, unit structs and enums are rejected with an error at their name.
+
+use derive_builder::{Getters, Setters};
+
+#[derive(Getters)]
+pub struct Pair(u8, u8);
+
+#[derive(Setters)]
+pub struct Unit;
+
+#[derive(Getters, Setters)]
+pub enum Either {
+    Left(u8),
+    Right,
+}
+
+fn main() {}
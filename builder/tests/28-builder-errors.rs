@@ -0,0 +1,19 @@
+// The setters of a builder are named after the fields they set, so `Builder`
+// can only be derived for structs with named fields. Tuple structs, unit
+// structs and enums are rejected with an error at their name.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Pair(u8, u8);
+
+#[derive(Builder)]
+pub struct Unit;
+
+#[derive(Builder)]
+pub enum Either {
+    Left(u8),
+    Right,
+}
+
+fn main() {}
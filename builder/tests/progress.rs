@@ -10,4 +10,25 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-patch.rs");
+    t.pass("tests/11-buildable.rs");
+    t.pass("tests/12-closure-setters.rs");
+    t.pass("tests/13-option-setter.rs");
+    t.pass("tests/14-build-owned.rs");
+    t.compile_fail("tests/15-attribute-errors.rs");
+    t.pass("tests/16-skip.rs");
+    t.pass("tests/17-wrap-pointers.rs");
+    t.pass("tests/18-borrowed-fields.rs");
+    t.pass("tests/19-no-std.rs");
+    t.pass("tests/20-getters-setters.rs");
+    t.pass("tests/21-build-async.rs");
+    t.pass("tests/22-each-item.rs");
+    t.compile_fail("tests/23-each-errors.rs");
+    t.pass("tests/24-lints.rs");
+    t.compile_fail("tests/25-must-use.rs");
+    if cfg!(feature = "serde") {
+        t.pass("tests/26-serialize.rs");
+    }
+    t.compile_fail("tests/27-accessor-errors.rs");
+    t.compile_fail("tests/28-builder-errors.rs");
 }
@@ -58,66 +58,88 @@ use syn::spanned::Spanned;
 
 struct ImplData<'a> {
     generics: &'a syn::Generics,
-    fields: &'a ::std::vec::Vec<FieldsData>,
-    fmt_str: ::std::vec::Vec<::std::option::Option<::std::string::String>>,
+    shape: &'a Shape,
 }
 
 impl<'a> ImplData<'a> {
-    pub fn new(
-        generics: &'a syn::Generics,
-        fields: &'a ::std::vec::Vec<FieldsData>,
-    ) -> Self {
-        let fmt_str = Self::generate_fmt_string(fields);
-        Self {
-            generics,
-            fields,
-            fmt_str,
-        }
+    pub fn new(generics: &'a syn::Generics, shape: &'a Shape) -> Self {
+        Self { generics, shape }
     }
 
-    fn generate_fmt_string(
-        fields: &::std::vec::Vec<FieldsData>,
-    ) -> ::std::vec::Vec<::std::option::Option<::std::string::String>> {
-        fields
-            .iter()
-            .map(|f| {
-                let out = format!(
-                    "{}",
-                    f.fmt_pattern.as_ref().map(|f| f.as_str()).unwrap_or("{:?}")
+    pub fn generate(&self, type_name: &syn::Ident) -> proc_macro2::TokenStream {
+        let body = match self.shape {
+            Shape::Struct(variant) => variant.generate_fmt(&variant.self_bindings()),
+            Shape::Transparent(variant) => {
+                let field = &variant.fields[0];
+                let binding = &variant.self_bindings()[0];
+                match (&field.redact, &field.with, &field.fmt_pattern) {
+                    _ if field.each.is_some() => {
+                        let write = field.each.as_ref().unwrap().generate_write();
+                        quote::quote!({
+                            let __value = &#binding;
+                            #write
+                        })
+                    },
+                    (Some(placeholder), _, _) => quote::quote!(f.write_str(#placeholder)),
+                    (None, Some(with), _) => quote::quote!(#with(&#binding, f)),
+                    (None, None, Some(fmt)) => {
+                        fmt.generate_write(&quote::quote!(#binding))
+                    },
+                    (None, None, None) => {
+                        quote::quote!(::std::fmt::Debug::fmt(&#binding, f))
+                    },
+                }
+            },
+            // A reference to an enum without variants is still inhabited, so
+            // only the enum itself can be matched exhaustively with no arms.
+            Shape::Enum(variants) if variants.is_empty() => quote::quote!(match *self {}),
+            Shape::Enum(variants) => {
+                let arms = variants.iter().map(|v| v.generate_arm());
+                quote::quote!(
+                    match self {
+                        #(#arms)*
+                    }
+                )
+            },
+        };
+
+        // `.field()` takes a `&dyn Debug`, so a `with` function or a format
+        // string is wrapped in a value that calls it from its own `Debug` impl,
+        // as is every element of an `each` collection. The wrapper is generic
+        // over the field type instead of using the generics of the impl, which
+        // items nested in a function cannot name.
+        let transparent = matches!(self.shape, Shape::Transparent(_));
+        let uses_debug_with = self.shape.fields().any(|f| {
+            f.reads_value()
+                && (f.each.is_some()
+                    || !transparent && (f.with.is_some() || f.fmt_pattern.is_some()))
+        });
+        let debug_with = if uses_debug_with {
+            quote::quote!(
+                struct __DebugWith<'a, T: ?Sized>(
+                    &'a T,
+                    fn(&T, &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result,
                 );
-                Some(out)
-            })
-            .collect()
-    }
 
-    pub fn generate(&self, struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-        let (debug_fields, debug_fmt_str): (
-            Vec<&FieldsData>,
-            Vec<&::std::string::String>,
-        ) = self
-            .fields
-            .iter()
-            .zip(&self.fmt_str)
-            .filter(|(f, fmt_str)| {
-                if let syn::Type::Path(syn::TypePath { path, .. }) = &f.ty {
-                    return fmt_str.is_some()
-                        && !path.segments.iter().any(|s| s.ident == "PhantomData");
+                impl<T: ?Sized> ::std::fmt::Debug for __DebugWith<'_, T> {
+                    fn fmt(
+                        &self,
+                        f: &mut ::std::fmt::Formatter<'_>,
+                    ) -> ::std::fmt::Result {
+                        (self.1)(self.0, f)
+                    }
                 }
-                fmt_str.is_some()
-            })
-            .map(|(f, fmt_str)| (f, fmt_str.as_ref().unwrap()))
-            .unzip();
-
-        let field_names: ::std::vec::Vec<&::std::option::Option<proc_macro2::Ident>> =
-            debug_fields.iter().map(|f| &f.ident).collect();
+            )
+        } else {
+            proc_macro2::TokenStream::new()
+        };
 
         let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
         quote::quote!(
-            impl #impl_generics ::std::fmt::Debug for #struct_name #ty_generics #where_clause {
+            impl #impl_generics ::std::fmt::Debug for #type_name #ty_generics #where_clause {
                 fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                    f.debug_struct(stringify!(#struct_name))
-                     #(.field(stringify!(#field_names), &format_args!(#debug_fmt_str, self.#field_names)))*
-                     .finish()
+                    #debug_with
+                    #body
                 }
             }
 
@@ -125,117 +147,438 @@ impl<'a> ImplData<'a> {
     }
 }
 
+/// The fields of the type being derived: a struct has a single set of them,
+/// an enum one per variant. A `#[debug(transparent)]` struct has exactly one
+/// field and is printed as that field alone.
+enum Shape {
+    Struct(VariantData),
+    Transparent(VariantData),
+    Enum(::std::vec::Vec<VariantData>),
+}
+
+impl Shape {
+    pub fn fields(&self) -> impl Iterator<Item = &FieldsData> {
+        let variants = match self {
+            Shape::Struct(variant) | Shape::Transparent(variant) => {
+                ::std::slice::from_ref(variant)
+            },
+            Shape::Enum(variants) => variants.as_slice(),
+        };
+        variants.iter().flat_map(|v| &v.fields)
+    }
+
+    /// The types that bounds are inferred from: those of the fields formatted
+    /// through `Debug` without an explicit `bound`. Skipped, redacted and
+    /// `PhantomData` fields are never formatted, and fields with a `with`
+    /// function decide on their own bounds.
+    pub fn field_types(&self) -> ::std::vec::Vec<syn::Type> {
+        self.fields()
+            .filter(|f| f.bound.is_none() && f.needs_debug())
+            .map(|f| f.ty.clone())
+            .collect()
+    }
+
+    pub fn field_bounds(&self) -> ::std::vec::Vec<syn::WherePredicate> {
+        self.fields()
+            .flat_map(|f| f.bound.iter().flatten().cloned())
+            .collect()
+    }
+}
+
+enum FieldsStyle {
+    Named,
+    Unnamed,
+    Unit,
+}
+
+struct VariantData {
+    ident: syn::Ident,
+    style: FieldsStyle,
+    fields: ::std::vec::Vec<FieldsData>,
+}
+
+impl VariantData {
+    /// The fields of a struct, read through `self`.
+    pub fn self_bindings(&self) -> ::std::vec::Vec<proc_macro2::TokenStream> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => quote::quote!(self.#ident),
+                None => {
+                    let index = syn::Index::from(i);
+                    quote::quote!(self.#index)
+                },
+            })
+            .collect()
+    }
+
+    /// Writes the fields to the formatter `f` the way the standard derive
+    /// does, reading each field through the matching entry of `bindings`,
+    /// which are place expressions of the field's type.
+    pub fn generate_fmt(
+        &self,
+        bindings: &[proc_macro2::TokenStream],
+    ) -> proc_macro2::TokenStream {
+        let name = &self.ident;
+        let (debug_fields, debug_bindings): (
+            ::std::vec::Vec<&FieldsData>,
+            ::std::vec::Vec<&proc_macro2::TokenStream>,
+        ) = self
+            .fields
+            .iter()
+            .zip(bindings)
+            .filter(|(f, _)| !f.is_omitted())
+            .unzip();
+        let debug_values = debug_fields
+            .iter()
+            .zip(debug_bindings)
+            .map(|(f, binding)| f.generate_value(binding));
+        // Readers are told that something was left out on purpose; a
+        // `PhantomData` marker holds nothing worth mentioning.
+        let finish = if self.fields.iter().any(|f| f.skip) {
+            quote::quote!(finish_non_exhaustive)
+        } else {
+            quote::quote!(finish)
+        };
+
+        match self.style {
+            FieldsStyle::Named => {
+                let field_names = debug_fields.iter().map(|f| &f.ident);
+                quote::quote!(
+                    f.debug_struct(stringify!(#name))
+                     #(.field(stringify!(#field_names), #debug_values))*
+                     .#finish()
+                )
+            },
+            FieldsStyle::Unnamed => quote::quote!(
+                f.debug_tuple(stringify!(#name))
+                 #(.field(#debug_values))*
+                 .#finish()
+            ),
+            FieldsStyle::Unit => quote::quote!(f.write_str(stringify!(#name))),
+        }
+    }
+
+    /// A match arm for this variant of an enum. Fields left out of the output
+    /// are matched with `_` so that no binding goes unused.
+    pub fn generate_arm(&self) -> proc_macro2::TokenStream {
+        let name = &self.ident;
+        let (patterns, bindings): (
+            ::std::vec::Vec<proc_macro2::TokenStream>,
+            ::std::vec::Vec<proc_macro2::TokenStream>,
+        ) = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let binding = quote::format_ident!("__self_{}", i);
+                let pattern = if !f.reads_value() {
+                    quote::quote!(_)
+                } else {
+                    quote::quote!(#binding)
+                };
+                (pattern, quote::quote!((*#binding)))
+            })
+            .unzip();
+        let pattern = match self.style {
+            FieldsStyle::Named => {
+                let field_names = self.fields.iter().map(|f| &f.ident);
+                quote::quote!(Self::#name { #(#field_names: #patterns),* })
+            },
+            FieldsStyle::Unnamed => quote::quote!(Self::#name(#(#patterns),*)),
+            FieldsStyle::Unit => quote::quote!(Self::#name),
+        };
+        let body = self.generate_fmt(&bindings);
+        quote::quote!(#pattern => #body,)
+    }
+}
+
 struct FieldsData {
     ident: ::std::option::Option<syn::Ident>,
-    fmt_pattern: ::std::option::Option<::std::string::String>,
+    fmt_pattern: ::std::option::Option<FormatString>,
     ty: ::syn::Type,
+    /// Set by `#[debug(bound = "...")]`, replacing the bounds that would
+    /// otherwise be inferred from this field's type.
+    bound: ::std::option::Option<WherePredicates>,
+    skip: bool,
+    /// The placeholder printed instead of a `#[debug(redact)]` field.
+    redact: ::std::option::Option<::std::string::String>,
+    /// A `fn(&T, &mut Formatter) -> fmt::Result` given by `#[debug(with = ...)]`.
+    with: ::std::option::Option<syn::Path>,
+    /// The format string for each element, given by `#[debug(each = "...")]`.
+    each: ::std::option::Option<Each>,
 }
 
-enum GenericVariant {
-    Associative(syn::Type),
-    SameGeneric,
-    Phantom,
-    DifferentFromGeneric,
-}
-
-impl GenericVariant {
-    pub fn prioritize(new_variant: Self, old_variant: Self) -> Self {
-        match (&new_variant, &old_variant) {
-            (_, Self::Phantom) => Self::Phantom,
-            (Self::Phantom, _) => Self::Phantom,
-            (_, Self::Associative(_)) => old_variant,
-            (Self::Associative(_), _) => new_variant,
-            (_, Self::SameGeneric) => Self::SameGeneric,
-            (Self::SameGeneric, _) => Self::SameGeneric,
-            _ => new_variant,
+type WherePredicates = syn::punctuated::Punctuated<syn::WherePredicate, syn::Token![,]>;
+
+impl FieldsData {
+    /// The value passed to `.field()` for this field.
+    pub fn generate_value(
+        &self,
+        binding: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match (&self.redact, &self.with) {
+            _ if self.each.is_some() => {
+                let write = self.each.as_ref().unwrap().generate_write();
+                quote::quote!(&__DebugWith(&#binding, |__value, f| #write))
+            },
+            (Some(placeholder), _) => quote::quote!(&format_args!("{}", #placeholder)),
+            (None, Some(with)) => quote::quote!(&__DebugWith(&#binding, #with)),
+            (None, None) => match &self.fmt_pattern {
+                // A closure that captures nothing coerces to the `fn` pointer
+                // that `__DebugWith` holds.
+                Some(fmt) => {
+                    let write = fmt.generate_write(&quote::quote!(*__value));
+                    quote::quote!(&__DebugWith(&#binding, |__value, f| #write))
+                },
+                None => quote::quote!(&#binding),
+            },
         }
     }
 
-    // pub fn display(&self) {
-    //     match &self {
-    //         Self::Phantom => println!("PHANTOM"),
-    //         Self::Associative(a) => println!("ASSOCIATIVE {}", a.to_token_stream()),
-    //         Self::SameGeneric => println!("SAME GENERIC"),
-    //         Self::DifferentFromGeneric => println!("DIFFERENT FROM THE GENERIC"),
-    //     }
-    // }
+    /// Whether the field is left out of the output entirely.
+    pub fn is_omitted(&self) -> bool {
+        self.skip || self.is_phantom()
+    }
+
+    /// Whether the output depends on the field's value.
+    pub fn reads_value(&self) -> bool {
+        !self.is_omitted() && self.redact.is_none()
+    }
+
+    /// Whether the field's value is formatted through its `Debug` impl, as
+    /// opposed to a `with` function that decides on its own bounds.
+    pub fn needs_debug(&self) -> bool {
+        self.reads_value() && self.with.is_none()
+    }
+
+    /// `PhantomData` markers carry no data and are left out of the output.
+    pub fn is_phantom(&self) -> bool {
+        if let syn::Type::Path(syn::TypePath { path, .. }) = &self.ty {
+            return path.segments.iter().any(|s| s.ident == "PhantomData");
+        }
+        false
+    }
+}
+
+/// What formatting a field needs from one generic parameter.
+enum GenericVariant {
+    /// A type built from the parameter that has to be `Debug` as a whole,
+    /// such as the associated type in `T::Value`.
+    Associative(::std::boxed::Box<syn::Type>),
+    /// The parameter itself is formatted, so it needs `T: Debug`.
+    SameGeneric,
 }
 
 #[proc_macro_derive(CustomDebug, attributes(debug, bound))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as syn::DeriveInput);
-    let struct_name = ast.ident;
-    let fields_info = match &ast.data {
-        ::syn::Data::Struct(data_struct) => match &data_struct.fields {
-            ::syn::Fields::Named(field) => field.named.clone(),
-            _ => unimplemented!(),
+    let type_name = ast.ident;
+    let mut errors: ::std::option::Option<syn::Error> = None;
+    let (outer_attrs, outer_errors) = process_outer_attrs(&ast.attrs);
+    if let Some(e) = outer_errors {
+        combine_error(&mut errors, e);
+    }
+    let shape = match &ast.data {
+        ::syn::Data::Struct(data_struct) => {
+            let (variant, error) =
+                process_variant(type_name.clone(), &data_struct.fields);
+            if let Some(e) = error {
+                combine_error(&mut errors, e);
+            }
+            match outer_attrs.transparent {
+                Some(span) if variant.fields.iter().any(|f| f.skip) => {
+                    combine_error(
+                        &mut errors,
+                        syn::Error::new(
+                            span,
+                            "the field of a `transparent` struct cannot be skipped",
+                        ),
+                    );
+                    Shape::Struct(variant)
+                },
+                Some(_) if variant.fields.len() == 1 => Shape::Transparent(variant),
+                Some(span) => {
+                    combine_error(
+                        &mut errors,
+                        syn::Error::new(
+                            span,
+                            "`transparent` requires a struct with exactly one field",
+                        ),
+                    );
+                    Shape::Struct(variant)
+                },
+                None => Shape::Struct(variant),
+            }
+        },
+        ::syn::Data::Enum(data_enum) => {
+            if let Some(span) = outer_attrs.transparent {
+                combine_error(
+                    &mut errors,
+                    syn::Error::new(span, "`transparent` is only supported on structs"),
+                );
+            }
+            let variants = data_enum
+                .variants
+                .iter()
+                .map(|v| {
+                    for attr in v.attrs.iter().filter(|a| a.path().is_ident("debug")) {
+                        combine_error(
+                            &mut errors,
+                            syn::Error::new_spanned(
+                                attr,
+                                "debug attributes are not supported on enum variants; \
+                                 put them on the enum or on the variant's fields",
+                            ),
+                        );
+                    }
+                    let (variant, error) = process_variant(v.ident.clone(), &v.fields);
+                    if let Some(e) = error {
+                        combine_error(&mut errors, e);
+                    }
+                    variant
+                })
+                .collect();
+            Shape::Enum(variants)
+        },
+        ::syn::Data::Union(data_union) => {
+            let err = syn::Error::new(
+                data_union.union_token.span,
+                "CustomDebug cannot be derived for unions",
+            );
+            return proc_macro::TokenStream::from(err.to_compile_error());
         },
-        _ => unimplemented!(),
     };
-    // process_outer_attrs(ast.attrs);
-    let (fields, _possible_errors): (
-        ::std::vec::Vec<FieldsData>,
-        ::std::vec::Vec<::std::option::Option<::syn::Error>>,
-    ) = process_fields(&fields_info).into_iter().unzip();
-    let generics = ast.generics;
-    let field_types = fields.iter().map(|f| f.ty.clone()).collect();
-    let generics_or_err = compute_trait_bounds(generics, &field_types);
-    if let Err(err) = generics_or_err {
-        let quote_err = err.to_compile_error();
-        return proc_macro::TokenStream::from(quote::quote!(#quote_err));
-    }
-    let generics = generics_or_err.unwrap();
-
-    let impl_data = generate_impl_data(&generics, &fields);
-    let impls = impl_data.generate(&struct_name);
+    // The generated impl would only repeat these errors less clearly, for
+    // example as a failing `format_args!`, so nothing else is emitted.
+    if let Some(errors) = errors {
+        return proc_macro::TokenStream::from(errors.to_compile_error());
+    }
+    let mut generics = ast.generics;
+    // A struct-level `bound` replaces every inferred bound, including those
+    // of fields with their own `bound`.
+    if let Some(bound) = outer_attrs.bound {
+        generics.make_where_clause().predicates.extend(bound);
+    } else {
+        let field_types = shape.field_types();
+        let generics_or_err = compute_trait_bounds(generics, &field_types);
+        if let Err(err) = generics_or_err {
+            let quote_err = err.to_compile_error();
+            return proc_macro::TokenStream::from(quote::quote!(#quote_err));
+        }
+        generics = generics_or_err.unwrap();
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(shape.field_bounds());
+    }
+
+    let impl_data = generate_impl_data(&generics, &shape);
+    let impls = impl_data.generate(&type_name);
     let expanded = quote::quote!(
         #impls
     );
     proc_macro::TokenStream::from(expanded)
 }
 
-// fn process_outer_attrs(
-//     attrs: ::std::vec::Vec<syn::Attribute>,
-// ) -> ::std::result::Result<(), syn::Error> {
-//     for attr in attrs {
-//         println!("{}", attr.to_token_stream());
-//         if attr.path().is_ident("debug") {
-//             let mut bound_ty = String::new();
-//             let mut binding = String::new();
-//             attr.parse_nested_meta(|meta| {
-//                 if meta.path.is_ident("bound") {
-//                     let value = meta.value()?;
-//                     println!("TYPE VALUE {}", value);
-//                     Ok(())
-//                 } else {
-//                     Err(syn::Error::new(meta.path.span(), "unsupported attribute"))
-//                 }
-//             })?
-//         }
-//     }
-//     unimplemented!()
-// }
+#[derive(Default)]
+struct OuterAttrs {
+    transparent: ::std::option::Option<proc_macro2::Span>,
+    bound: ::std::option::Option<WherePredicates>,
+}
+
+fn process_outer_attrs(
+    attrs: &[syn::Attribute],
+) -> (OuterAttrs, ::std::option::Option<syn::Error>) {
+    let mut outer_attrs = OuterAttrs::default();
+    let mut errors: ::std::option::Option<syn::Error> = None;
+    let mut seen = ::std::vec::Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                check_duplicate(&mut seen, &mut errors, &meta.path);
+                outer_attrs.transparent = Some(meta.path.span());
+            } else if meta.path.is_ident("bound") {
+                check_duplicate(&mut seen, &mut errors, &meta.path);
+                outer_attrs.bound = Some(parse_bound(&meta)?);
+            } else {
+                combine_error(&mut errors, unknown_attr(&meta));
+                skip_meta_value(&meta)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = parsed {
+            combine_error(&mut errors, e);
+        }
+    }
+    (outer_attrs, errors)
+}
+
+fn process_variant(
+    ident: syn::Ident,
+    ast_fields: &syn::Fields,
+) -> (VariantData, ::std::option::Option<syn::Error>) {
+    let style = match ast_fields {
+        syn::Fields::Named(_) => FieldsStyle::Named,
+        syn::Fields::Unnamed(_) => FieldsStyle::Unnamed,
+        syn::Fields::Unit => FieldsStyle::Unit,
+    };
+    let (fields, possible_errors): (
+        ::std::vec::Vec<FieldsData>,
+        ::std::vec::Vec<::std::option::Option<::syn::Error>>,
+    ) = process_fields(ast_fields).into_iter().unzip();
+    let errors = possible_errors
+        .into_iter()
+        .flatten()
+        .reduce(|mut errors, e| {
+            errors.combine(e);
+            errors
+        });
+    (
+        VariantData {
+            ident,
+            style,
+            fields,
+        },
+        errors,
+    )
+}
 
 fn process_fields(
-    ast_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ast_fields: &syn::Fields,
 ) -> ::std::vec::Vec<(FieldsData, ::std::option::Option<::syn::Error>)> {
     ast_fields
         .iter()
         .map(|field| {
             let ident = field.ident.clone();
-            let mut error: ::std::option::Option<::syn::Error> = None;
-            let fmt_pattern = match debug_attrs(&field.attrs) {
-                Ok(p) => p,
-                Err(e) => {
-                    error = Some(e);
+            let (attrs, mut error) = debug_attrs(&field.attrs);
+            let each = attrs.each.and_then(|fmt| match Collection::of(&field.ty) {
+                Some(collection) => Some(Each { collection, fmt }),
+                None => {
+                    combine_error(
+                        &mut error,
+                        syn::Error::new(
+                            field.ty.span(),
+                            "`each` applies to a Vec, slice, array, map or Option field",
+                        ),
+                    );
                     None
                 },
-            };
+            });
             (
                 FieldsData {
                     ident,
-                    fmt_pattern,
+                    fmt_pattern: attrs.fmt_pattern,
                     ty: field.ty.clone(),
+                    bound: attrs.bound,
+                    skip: attrs.skip,
+                    redact: attrs.redact,
+                    with: attrs.with,
+                    each,
                 },
                 error,
             )
@@ -243,194 +586,747 @@ fn process_fields(
         .collect()
 }
 
+#[derive(Default)]
+struct FieldAttrs {
+    fmt_pattern: ::std::option::Option<FormatString>,
+    bound: ::std::option::Option<WherePredicates>,
+    skip: bool,
+    redact: ::std::option::Option<::std::string::String>,
+    with: ::std::option::Option<syn::Path>,
+    each: ::std::option::Option<FormatString>,
+}
+
+const DEFAULT_REDACTION: &str = "<redacted>";
+
+/// Reads `#[debug = "..."]` and the `#[debug(...)]` keys of a field: `bound`,
+/// `skip`, `redact`, `with` and `each`. Every problem is collected, so that
+/// a bad key does not hide the ones after it.
 fn debug_attrs(
-    attributes: &Vec<syn::Attribute>,
-) -> ::std::result::Result<Option<String>, syn::Error> {
+    attributes: &[syn::Attribute],
+) -> (FieldAttrs, ::std::option::Option<syn::Error>) {
+    let mut field_attrs = FieldAttrs::default();
+    let mut errors: ::std::option::Option<syn::Error> = None;
+    let mut seen = ::std::vec::Vec::new();
+    let mut redact_span = None;
+    let mut each_span = None;
     for attr in attributes {
-        if attr.path().is_ident("debug") {
-            let literal_string = match &attr.meta {
-                syn::Meta::NameValue(syn::MetaNameValue {
-                    value:
-                        syn::Expr::Lit(syn::ExprLit {
-                            lit: syn::Lit::Str(lit_str),
-                            ..
-                        }),
-                    ..
-                }) => Ok(lit_str.value()),
-                _ => Err(syn::Error::new(attr.span(), "invalid attribute syntax")),
-            }?;
-
-            return Ok(Some(literal_string));
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }),
+                ..
+            }) => {
+                check_duplicate(&mut seen, &mut errors, path);
+                match parse_format(lit_str) {
+                    Ok(fmt) => field_attrs.fmt_pattern = Some(fmt),
+                    Err(e) => combine_error(&mut errors, e),
+                }
+            },
+            syn::Meta::List(_) => {
+                let parsed = attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("bound") {
+                        check_duplicate(&mut seen, &mut errors, &meta.path);
+                        field_attrs.bound = Some(parse_bound(&meta)?);
+                    } else if meta.path.is_ident("skip") {
+                        check_duplicate(&mut seen, &mut errors, &meta.path);
+                        field_attrs.skip = true;
+                    } else if meta.path.is_ident("with") {
+                        check_duplicate(&mut seen, &mut errors, &meta.path);
+                        field_attrs.with = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("each") {
+                        check_duplicate(&mut seen, &mut errors, &meta.path);
+                        each_span = Some(meta.path.span());
+                        let lit_str: syn::LitStr = meta.value()?.parse()?;
+                        match parse_format(&lit_str) {
+                            Ok(fmt) => field_attrs.each = Some(fmt),
+                            Err(e) => combine_error(&mut errors, e),
+                        }
+                    } else if meta.path.is_ident("redact") {
+                        check_duplicate(&mut seen, &mut errors, &meta.path);
+                        redact_span = Some(meta.path.span());
+                        field_attrs.redact = Some(if meta.input.peek(syn::Token![=]) {
+                            meta.value()?.parse::<syn::LitStr>()?.value()
+                        } else {
+                            DEFAULT_REDACTION.to_string()
+                        });
+                    } else {
+                        combine_error(&mut errors, unknown_attr(&meta));
+                        skip_meta_value(&meta)?;
+                    }
+                    Ok(())
+                });
+                if let Err(e) = parsed {
+                    combine_error(&mut errors, e);
+                }
+            },
+            _ => combine_error(
+                &mut errors,
+                syn::Error::new(attr.span(), "invalid attribute syntax"),
+            ),
+        }
+    }
+    if let (true, Some(span)) = (field_attrs.skip, redact_span) {
+        combine_error(
+            &mut errors,
+            syn::Error::new(span, "a field cannot be both skipped and redacted"),
+        );
+    }
+    if let Some(with) = &field_attrs.with {
+        if field_attrs.fmt_pattern.is_some()
+            || field_attrs.redact.is_some()
+            || field_attrs.skip
+        {
+            combine_error(
+                &mut errors,
+                syn::Error::new(
+                    with.span(),
+                    "`with` cannot be combined with a format string, `skip` or `redact`",
+                ),
+            );
+        }
+    }
+    if let Some(span) = each_span.filter(|_| {
+        field_attrs.fmt_pattern.is_some()
+            || field_attrs.with.is_some()
+            || field_attrs.redact.is_some()
+            || field_attrs.skip
+    }) {
+        combine_error(
+            &mut errors,
+            syn::Error::new(
+                span,
+                "`each` cannot be combined with a format string, `with`, `skip` or `redact`",
+            ),
+        );
+    }
+    (field_attrs, errors)
+}
+
+/// Reports a key given more than once for the same item, where the later
+/// value would otherwise silently replace the earlier one. `#[debug = "..."]`
+/// counts as the key `debug`.
+fn check_duplicate(
+    seen: &mut ::std::vec::Vec<::std::string::String>,
+    errors: &mut ::std::option::Option<syn::Error>,
+    path: &syn::Path,
+) {
+    let key = path.to_token_stream().to_string();
+    if seen.contains(&key) {
+        let message = if key == "debug" {
+            "duplicate format string `#[debug = \"...\"]`".to_string()
+        } else {
+            format!("duplicate debug attribute `{}`", key)
+        };
+        combine_error(errors, syn::Error::new(path.span(), message));
+    } else {
+        seen.push(key);
+    }
+}
+
+/// Skips the value of an unknown key, so that the keys after it are still
+/// checked.
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.input.parse::<proc_macro2::TokenTree>()?;
+    }
+    Ok(())
+}
+
+/// The collections whose elements `#[debug(each = "...")]` can reach, told
+/// apart by the spelling of the field type.
+enum Collection {
+    /// Printed with `debug_list`: `Vec`, `VecDeque`, slices and arrays.
+    List,
+    /// Printed with `debug_map`, formatting the values only.
+    Map,
+    Option,
+}
+
+impl Collection {
+    /// Looks through references and smart pointers, which print the value
+    /// they point to.
+    fn of(ty: &syn::Type) -> ::std::option::Option<Self> {
+        match ty {
+            syn::Type::Slice(_) | syn::Type::Array(_) => Some(Self::List),
+            syn::Type::Reference(syn::TypeReference { elem, .. })
+            | syn::Type::Paren(syn::TypeParen { elem, .. })
+            | syn::Type::Group(syn::TypeGroup { elem, .. }) => Self::of(elem),
+            syn::Type::Path(syn::TypePath { qself: None, path }) => {
+                let segment = path.segments.last()?;
+                match segment.ident.to_string().as_str() {
+                    "Vec" | "VecDeque" => Some(Self::List),
+                    "HashMap" | "BTreeMap" => Some(Self::Map),
+                    "Option" => Some(Self::Option),
+                    "Box" | "Rc" | "Arc" => match &segment.arguments {
+                        syn::PathArguments::AngleBracketed(args) => {
+                            match args.args.first()? {
+                                syn::GenericArgument::Type(inner) => Self::of(inner),
+                                _ => None,
+                            }
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+struct Each {
+    collection: Collection,
+    fmt: FormatString,
+}
+
+impl Each {
+    /// Writes the collection behind the reference `__value` to `f`, keeping
+    /// its usual rendering and wrapping every element in `__DebugWith`.
+    pub fn generate_write(&self) -> proc_macro2::TokenStream {
+        let write = self.fmt.generate_write(&quote::quote!(*__value));
+        let element = quote::quote!(__DebugWith(__item, |__value, f| #write));
+        match self.collection {
+            Collection::List => quote::quote!(
+                f.debug_list()
+                    .entries(__value.iter().map(|__item| #element))
+                    .finish()
+            ),
+            Collection::Map => quote::quote!(
+                f.debug_map()
+                    .entries(__value.iter().map(|(__key, __item)| (__key, #element)))
+                    .finish()
+            ),
+            Collection::Option => quote::quote!(
+                match __value.iter().next() {
+                    ::std::option::Option::Some(__item) => {
+                        f.debug_tuple("Some").field(&#element).finish()
+                    },
+                    ::std::option::Option::None => f.write_str("None"),
+                }
+            ),
+        }
+    }
+}
+
+fn combine_error(errors: &mut ::std::option::Option<syn::Error>, error: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
+    }
+}
+
+fn unknown_attr(meta: &syn::meta::ParseNestedMeta) -> syn::Error {
+    let key = meta.path.to_token_stream().to_string();
+    syn::Error::new(
+        meta.path.span(),
+        format!("unknown debug attribute `{}`", key),
+    )
+}
+
+/// A `#[debug = "..."]` format string, split into the literal text between
+/// placeholders and the placeholders themselves.
+struct FormatString {
+    pieces: ::std::vec::Vec<Piece>,
+}
+
+enum Piece {
+    /// Text copied as written, with its `{{` and `}}` escapes.
+    Literal(::std::string::String),
+    Placeholder(Spec),
+}
+
+/// The part of a placeholder after the `:`, following the grammar of
+/// `std::fmt`: `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+#[derive(Default)]
+struct Spec {
+    fill_align: ::std::string::String,
+    sign: ::std::string::String,
+    alternate: bool,
+    zero: bool,
+    width: ::std::string::String,
+    precision: ::std::option::Option<::std::string::String>,
+    ty: ::std::string::String,
+}
+
+impl FormatString {
+    /// Whether some placeholder is `Debug` without an explicit `#`, so that
+    /// the output depends on `{:#?}` being used on the whole value.
+    pub fn inherits_alternate(&self) -> bool {
+        self.specs()
+            .any(|spec| !spec.alternate && spec.ty.ends_with('?'))
+    }
+
+    /// Whether some placeholder has no width of its own.
+    pub fn inherits_width(&self) -> bool {
+        self.specs().any(|spec| spec.width.is_empty())
+    }
+
+    /// Whether some placeholder has no precision of its own and formats with
+    /// a trait that reads one for numbers only. `Display` would also truncate
+    /// strings with it, which `Debug` never does.
+    pub fn inherits_precision(&self) -> bool {
+        self.specs().any(Spec::inherits_precision)
+    }
+
+    /// Renders the format string with every placeholder referring to the
+    /// field as argument `0`. Placeholders without a width take it from the
+    /// `__width` argument, and those without a precision that would read one
+    /// from `__precision` when `precision` is set; `alternate` adds `#` to
+    /// `Debug` placeholders.
+    pub fn render(&self, alternate: bool, precision: bool) -> ::std::string::String {
+        let mut rendered = ::std::string::String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(text) => rendered.push_str(text),
+                Piece::Placeholder(spec) => {
+                    rendered.push_str("{0:");
+                    rendered.push_str(&spec.fill_align);
+                    rendered.push_str(&spec.sign);
+                    if spec.alternate || (alternate && spec.ty.ends_with('?')) {
+                        rendered.push('#');
+                    }
+                    if spec.zero {
+                        rendered.push('0');
+                    }
+                    if spec.width.is_empty() {
+                        rendered.push_str("__width$");
+                    } else {
+                        rendered.push_str(&spec.width);
+                    }
+                    match &spec.precision {
+                        Some(digits) => {
+                            rendered.push('.');
+                            rendered.push_str(digits);
+                        },
+                        None if precision && spec.inherits_precision() => {
+                            rendered.push_str(".__precision$")
+                        },
+                        None => {},
+                    }
+                    rendered.push_str(&spec.ty);
+                    rendered.push('}');
+                },
+            }
+        }
+        rendered
+    }
+
+    /// Writes `value` to the formatter `f` with this format string, picking
+    /// the rendering that matches the flags `f` was created with.
+    pub fn generate_write(
+        &self,
+        value: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let alternates: &[bool] = if self.inherits_alternate() {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        let precisions: &[bool] = if self.inherits_precision() {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        let width_arg = &if self.inherits_width() {
+            quote::quote!(, __width = __width)
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        let arms = alternates.iter().flat_map(|&alternate| {
+            precisions.iter().map(move |&precision| {
+                let fmt_str = self.render(alternate, precision);
+                let alternate_pattern = if alternates.len() > 1 {
+                    quote::quote!(#alternate)
+                } else {
+                    quote::quote!(_)
+                };
+                let (precision_pattern, precision_arg) =
+                    match (precisions.len(), precision) {
+                        (1, _) => (quote::quote!(_), proc_macro2::TokenStream::new()),
+                        (_, false) => (
+                            quote::quote!(::std::option::Option::None),
+                            proc_macro2::TokenStream::new(),
+                        ),
+                        (_, true) => (
+                            quote::quote!(::std::option::Option::Some(__precision)),
+                            quote::quote!(, __precision = __precision),
+                        ),
+                    };
+                quote::quote!(
+                    (#alternate_pattern, #precision_pattern) => f.write_fmt(
+                        format_args!(#fmt_str, #value #width_arg #precision_arg)
+                    ),
+                )
+            })
+        });
+        let width = if self.inherits_width() {
+            quote::quote!(let __width = f.width().unwrap_or(0);)
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        quote::quote!({
+            #width
+            match (f.alternate(), f.precision()) {
+                #(#arms)*
+            }
+        })
+    }
+
+    fn specs(&self) -> impl Iterator<Item = &Spec> {
+        self.pieces.iter().filter_map(|piece| match piece {
+            Piece::Literal(_) => None,
+            Piece::Placeholder(spec) => Some(spec),
+        })
+    }
+}
+
+impl Spec {
+    fn inherits_precision(&self) -> bool {
+        self.precision.is_none() && matches!(self.ty.as_str(), "?" | "e" | "E")
+    }
+}
+
+/// Checks a `#[debug = "..."]` format string before it reaches `format_args!`,
+/// which would report problems against the whole derive. The field is the
+/// only argument, so every placeholder has to refer to it, and its spec has
+/// to be one that `format_args!` accepts. Errors point at the placeholder at
+/// fault where the compiler can locate it inside the literal, and name it in
+/// the message for when it cannot.
+fn parse_format(lit: &syn::LitStr) -> ::std::result::Result<FormatString, syn::Error> {
+    let value = lit.value();
+    let mut pieces = ::std::vec::Vec::new();
+    let mut literal_start = 0;
+    let mut next_implicit = 0;
+    let mut chars = value.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '{' if chars.next_if(|(_, c)| *c == '{').is_some() => {},
+            '}' if chars.next_if(|(_, c)| *c == '}').is_some() => {},
+            '{' => {
+                let Some((end, _)) = chars.find(|(_, c)| *c == '}') else {
+                    return Err(format_error(
+                        lit,
+                        start..value.len(),
+                        "unterminated `{`",
+                    ));
+                };
+                let placeholder = &value[start + 1..end];
+                let (argument, spec) = match placeholder.split_once(':') {
+                    Some((argument, spec)) => (argument, spec),
+                    None => (placeholder, ""),
+                };
+                let index = if argument.is_empty() {
+                    next_implicit += 1;
+                    next_implicit - 1
+                } else if let Ok(index) = argument.trim().parse::<usize>() {
+                    index
+                } else {
+                    return Err(format_error(
+                        lit,
+                        start..end + 1,
+                        &format!(
+                            "`{{{}}}` names an argument, but the field is the only \
+                             argument of this format string",
+                            placeholder,
+                        ),
+                    ));
+                };
+                if index != 0 {
+                    return Err(format_error(
+                        lit,
+                        start..end + 1,
+                        &format!(
+                            "`{{{}}}` refers to argument {}, but the field is the only \
+                             argument of this format string; use `{{0}}` to refer to \
+                             it more than once",
+                            placeholder, index,
+                        ),
+                    ));
+                }
+                let spec = parse_spec(spec).map_err(|message| {
+                    format_error(
+                        lit,
+                        start..end + 1,
+                        &format!("`{{{}}}`: {}", placeholder, message),
+                    )
+                })?;
+                pieces.push(Piece::Literal(value[literal_start..start].to_string()));
+                pieces.push(Piece::Placeholder(spec));
+                literal_start = end + 1;
+            },
+            '}' => {
+                return Err(format_error(lit, start..start + 1, "unmatched `}`"));
+            },
+            _ => {},
         }
     }
-    Ok(None)
+    if pieces.is_empty() {
+        return Err(syn::Error::new(
+            lit.span(),
+            "format string does not print the field; add a `{}` placeholder",
+        ));
+    }
+    pieces.push(Piece::Literal(value[literal_start..].to_string()));
+    Ok(FormatString { pieces })
+}
+
+/// An error pointing at `range` of the literal's value where the compiler can
+/// locate it, and at the whole literal otherwise.
+fn format_error(
+    lit: &syn::LitStr,
+    range: ::std::ops::Range<usize>,
+    message: &str,
+) -> syn::Error {
+    let token = lit.token();
+    let source = token.to_string();
+    // Offsets into the value only match the source when the literal has no
+    // escapes; raw strings add their `r#` prefix before the opening quote.
+    let offset = source.find('"').map(|quote| quote + 1);
+    let span = match offset {
+        Some(offset) if source[offset..].starts_with(lit.value().as_str()) => token
+            .subspan(range.start + offset..range.end + offset)
+            .unwrap_or_else(|| lit.span()),
+        _ => lit.span(),
+    };
+    syn::Error::new(span, message)
+}
+
+/// Splits the part of a placeholder after the `:` into its components.
+/// Widths and precisions taken from other arguments are rejected, since the
+/// field is the only one.
+fn parse_spec(spec: &str) -> ::std::result::Result<Spec, &'static str> {
+    let mut parsed = Spec::default();
+    let mut rest = spec;
+    let is_align = |c: char| matches!(c, '<' | '^' | '>');
+    let mut chars = rest.chars();
+    let fill_align = match (chars.next(), chars.next()) {
+        (Some(fill), Some(align)) if is_align(align) => fill.len_utf8() + 1,
+        (Some(align), _) if is_align(align) => 1,
+        _ => 0,
+    };
+    parsed.fill_align = rest[..fill_align].to_string();
+    rest = &rest[fill_align..];
+    if let Some(sign) = rest.strip_prefix(['+', '-']) {
+        parsed.sign = rest[..1].to_string();
+        rest = sign;
+    }
+    if let Some(alternate) = rest.strip_prefix('#') {
+        parsed.alternate = true;
+        rest = alternate;
+    }
+    if let Some(zero) = rest.strip_prefix('0') {
+        parsed.zero = true;
+        rest = zero;
+    }
+    let digits = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    parsed.width = rest[..rest.len() - digits.len()].to_string();
+    rest = digits;
+    if let Some(precision) = rest.strip_prefix('.') {
+        let digits = precision.trim_start_matches(|c: char| c.is_ascii_digit());
+        if digits.len() == precision.len() {
+            return Err("precision must be a number");
+        }
+        parsed.precision = Some(precision[..precision.len() - digits.len()].to_string());
+        rest = digits;
+    }
+    if rest.starts_with(['$', '*']) {
+        return Err(
+            "width and precision must be numbers, the field is the only argument",
+        );
+    }
+    match rest {
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p" => {
+            parsed.ty = rest.to_string();
+            Ok(parsed)
+        },
+        _ => Err("unknown format spec; expected a type such as `?`, `x`, `b` or `e`"),
+    }
+}
+
+/// Parses the where-predicates in `bound = "..."`. An empty string is
+/// allowed and stands for no bounds at all.
+fn parse_bound(
+    meta: &syn::meta::ParseNestedMeta,
+) -> ::std::result::Result<WherePredicates, syn::Error> {
+    let bound: syn::LitStr = meta.value()?.parse()?;
+    bound.parse_with(WherePredicates::parse_terminated)
 }
 
 fn compute_trait_bounds(
     mut generics: syn::Generics,
-    types: &::std::vec::Vec<syn::Type>,
+    types: &[syn::Type],
 ) -> ::std::result::Result<syn::Generics, syn::Error> {
     let mut where_generics = generics.clone();
     for param in &mut generics.params {
-        match param {
-            syn::GenericParam::Type(ref mut type_param) => {
-                let generic_ident = &type_param.ident;
-                let generic_variant = compute_generic_variant(&types, generic_ident);
-                if generic_variant.iter().any(|v| match v {
-                    GenericVariant::SameGeneric => true,
-                    _ => false,
-                }) {
-                    type_param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
-                }
-                if let Some(GenericVariant::Associative(parsed_ty)) =
-                    generic_variant.iter().find(|v| match v {
-                        GenericVariant::Associative(_) => true,
-                        _ => false,
-                    })
-                {
-                    let where_clause = where_generics.make_where_clause();
-                    if !where_clause.predicates.iter().any(|pred| {
-                        if let syn::WherePredicate::Type(syn::PredicateType {
-                            bounded_ty,
-                            ..
-                        }) = pred
-                        {
-                            quote::quote!(#bounded_ty).to_string()
-                                == quote::quote!(#parsed_ty).to_string()
-                        } else {
-                            false
-                        }
-                    }) {
-                        let bound = syn::TypeParamBound::Trait(syn::TraitBound {
-                            paren_token: None,
-                            modifier: syn::TraitBoundModifier::None,
-                            lifetimes: None,
-                            path: syn::parse_quote!(::std::fmt::Debug),
-                        });
-                        let mut bounds: syn::punctuated::Punctuated<
-                            syn::TypeParamBound,
-                            syn::Token![+],
-                        > = syn::punctuated::Punctuated::new();
-                        bounds.push(bound);
-                        where_clause.predicates.push(syn::WherePredicate::Type(
-                            syn::PredicateType {
-                                lifetimes: None,
-                                bounded_ty: parsed_ty.clone(),
-                                colon_token: syn::token::Colon(parsed_ty.span()),
-                                bounds,
-                            },
-                        ))
-                    } else {
-                        where_clause.predicates.iter_mut().for_each(|pred| {
-                            if let syn::WherePredicate::Type(syn::PredicateType {
-                                ref bounded_ty,
-
-                                bounds,
-                                ..
-                            }) = pred
-                            {
-                                if quote::quote!(#bounded_ty).to_string()
-                                    == quote::quote!(#parsed_ty).to_string()
-                                {
-                                    bounds.push(syn::parse_quote!(::std::fmt::Debug));
-                                }
-                            }
-                        })
-                    };
+        if let syn::GenericParam::Type(ref mut type_param) = param {
+            let generic_ident = &type_param.ident;
+            // Fields that never mention this parameter, such as an `u32` in
+            // another enum variant, say nothing about the bound it needs.
+            let mentioning: ::std::vec::Vec<syn::Type> = types
+                .iter()
+                .filter(|ty| mentions_ident(ty.to_token_stream(), generic_ident))
+                .cloned()
+                .collect();
+            let generic_variants = compute_generic_variant(&mentioning, generic_ident);
+            if generic_variants
+                .iter()
+                .any(|v| matches!(v, GenericVariant::SameGeneric))
+            {
+                type_param.bounds.push(syn::parse_quote!(::std::fmt::Debug));
+            }
+            for variant in &generic_variants {
+                if let GenericVariant::Associative(parsed_ty) = variant {
+                    add_debug_predicate(where_generics.make_where_clause(), parsed_ty);
                 }
-            },
-            _ => (),
-        };
+            }
+        }
     }
     generics.where_clause = where_generics.where_clause;
     Ok(generics)
 }
 
+/// Adds `ty: Debug` to the where-clause, next to the bounds the type already
+/// has there if any. Types are compared by their syntax, so each one ends up
+/// with a single predicate however many fields or parameters ask for it.
+fn add_debug_predicate(where_clause: &mut syn::WhereClause, parsed_ty: &syn::Type) {
+    let existing = where_clause
+        .predicates
+        .iter_mut()
+        .find_map(|pred| match pred {
+            syn::WherePredicate::Type(syn::PredicateType {
+                bounded_ty, bounds, ..
+            }) if bounded_ty == parsed_ty => Some(bounds),
+            _ => None,
+        });
+    let debug: syn::TypeParamBound = syn::parse_quote!(::std::fmt::Debug);
+    match existing {
+        Some(bounds) => {
+            if !bounds.iter().any(|bound| *bound == debug) {
+                bounds.push(debug);
+            }
+        },
+        None => where_clause
+            .predicates
+            .push(syn::parse_quote!(#parsed_ty: #debug)),
+    }
+}
+
 fn compute_generic_variant(
-    types: &::std::vec::Vec<syn::Type>,
+    types: &[syn::Type],
     generic_ident: &syn::Ident,
 ) -> ::std::vec::Vec<GenericVariant> {
-    types
-        .iter()
-        .map(|ty| match ty {
-            syn::Type::Path(syn::TypePath {
-                path: syn::Path { segments, .. },
-                ..
-            }) => segments.iter().fold(
-                GenericVariant::DifferentFromGeneric,
-                |variant, segment| {
-                    // Associative type from this generic
-                    let inner_variant = if let Ok(associative_type) =
-                        syn::parse2(segments.to_token_stream())
-                    {
-                        GenericVariant::Associative(associative_type)
-                    } else if &segment.ident == generic_ident {
-                        GenericVariant::SameGeneric
-                    } else {
-                        recursive_search_over_arguments_for_type(
-                            &segment.arguments,
-                            &generic_ident,
-                        )
-                    };
-                    GenericVariant::prioritize(inner_variant, variant)
-                },
-            ),
-            _ => GenericVariant::DifferentFromGeneric,
-        })
-        .collect()
+    let mut variants = ::std::vec::Vec::new();
+    for ty in types {
+        search_type(ty, generic_ident, &mut variants);
+    }
+    variants
+}
+
+/// Walks a field type down to the places where `generic_ident` appears and
+/// records what each of them needs to be formatted.
+fn search_type(
+    ty: &syn::Type,
+    generic_ident: &syn::Ident,
+    variants: &mut ::std::vec::Vec<GenericVariant>,
+) {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let segments = &path.segments;
+            if segments[0].ident == *generic_ident && path.leading_colon.is_none() {
+                variants.push(if segments.len() == 1 {
+                    GenericVariant::SameGeneric
+                } else {
+                    // Associative type from this generic, such as `T::Value`
+                    // or `T::Assoc::Nested`
+                    GenericVariant::Associative(::std::boxed::Box::new(ty.clone()))
+                });
+            } else if segments.last().is_some_and(|s| s.ident == "PhantomData") {
+                // `PhantomData<T>` is `Debug` for every `T`.
+            } else {
+                for segment in segments {
+                    search_arguments(&segment.arguments, generic_ident, variants);
+                }
+            }
+        },
+        // `<T as Trait>::Value` is an associative type as well, and so is
+        // anything reached from a qualified self that mentions the parameter.
+        syn::Type::Path(syn::TypePath { qself: Some(_), .. })
+            if mentions_ident(ty.to_token_stream(), generic_ident) =>
+        {
+            variants.push(GenericVariant::Associative(::std::boxed::Box::new(
+                ty.clone(),
+            )))
+        },
+        // `&T`, `[T]` and `[T; N]` are `Debug` exactly when `T` is.
+        syn::Type::Reference(syn::TypeReference { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. })
+        | syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => {
+            search_type(elem, generic_ident, variants)
+        },
+        syn::Type::Tuple(syn::TypeTuple { elems, .. }) => {
+            for elem in elems {
+                search_type(elem, generic_ident, variants);
+            }
+        },
+        // Raw and function pointers print as addresses, whatever they point
+        // to, and `!` has no values to print.
+        syn::Type::Ptr(_) | syn::Type::BareFn(_) | syn::Type::Never(_) => {},
+        // Trait objects and macros are bounded as a whole, since the parameter
+        // alone does not decide whether they are `Debug`.
+        _ if mentions_ident(ty.to_token_stream(), generic_ident) => variants.push(
+            GenericVariant::Associative(::std::boxed::Box::new(ty.clone())),
+        ),
+        _ => {},
+    }
 }
 
-fn recursive_search_over_arguments_for_type(
+fn mentions_ident(tokens: proc_macro2::TokenStream, ident: &syn::Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(i) => i == *ident,
+        proc_macro2::TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Searches the `<...>` arguments of a path segment, including associated
+/// type bindings such as `Item = T`. The parenthesized arguments of `Fn(T)`
+/// only appear in trait objects, which are bounded as a whole.
+fn search_arguments(
     args: &syn::PathArguments,
     generic_ident: &syn::Ident,
-) -> GenericVariant {
+    variants: &mut ::std::vec::Vec<GenericVariant>,
+) {
     if let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
         args: inner_args,
         ..
     }) = args
     {
-        inner_args.iter().fold(
-            GenericVariant::DifferentFromGeneric,
-            |arg_variant, arg| match arg {
-                syn::GenericArgument::Type(syn::Type::Path(syn::TypePath {
-                    path: syn::Path { segments, .. },
-                    ..
-                })) => {
-                    // Associative type from this generic
-                    let seg_variant = if let Ok(associative_type) =
-                        syn::parse2(segments.to_token_stream())
-                    {
-                        GenericVariant::Associative(associative_type)
-                    } else {
-                        segments.iter().fold(
-                            GenericVariant::DifferentFromGeneric,
-                            |variant, s| {
-                                let inner_variant = if &s.ident == generic_ident {
-                                    GenericVariant::SameGeneric
-                                } else {
-                                    recursive_search_over_arguments_for_type(
-                                        &s.arguments,
-                                        generic_ident,
-                                    )
-                                };
-
-                                GenericVariant::prioritize(inner_variant, variant)
-                            },
-                        )
-                    };
-                    GenericVariant::prioritize(seg_variant, arg_variant)
+        for arg in inner_args {
+            match arg {
+                syn::GenericArgument::Type(ty)
+                | syn::GenericArgument::AssocType(syn::AssocType { ty, .. }) => {
+                    search_type(ty, generic_ident, variants)
                 },
-                _ => arg_variant,
-            },
-        )
-    } else {
-        GenericVariant::DifferentFromGeneric
+                _ => {},
+            }
+        }
     }
 }
 
 fn generate_impl_data<'a>(
     generics: &'a ::syn::Generics,
-    fields: &'a ::std::vec::Vec<FieldsData>,
+    shape: &'a Shape,
 ) -> ImplData<'a> {
-    ImplData::new(generics, fields)
+    ImplData::new(generics, shape)
 }
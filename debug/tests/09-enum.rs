@@ -0,0 +1,48 @@
+// Enums derive `CustomDebug` as well, with unit, tuple and struct variants all
+// printed the way the standard `Debug` derive prints them. Fields of any
+// variant can carry their own `#[debug = "..."]` format.
+//
+// The bounds of the generated impl are inferred from the fields of every
+// variant, so a type parameter that only appears in one variant is still
+// required to implement `Debug`.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub enum Event<T> {
+    Started,
+    Progress(u32, #[debug = "{:.1}%"] f64),
+    Finished {
+        #[debug = "0x{:04x}"]
+        code: u16,
+        output: T,
+    },
+    Marker(PhantomData<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_eq!(format!("{:?}", Event::<()>::Started), "Started");
+    assert_eq!(
+        format!("{:?}", Event::<()>::Progress(3, 42.25)),
+        "Progress(3, 42.2%)"
+    );
+    let finished = Event::Finished {
+        code: 255,
+        output: "done",
+    };
+    assert_eq!(
+        format!("{:?}", finished),
+        r#"Finished { code: 0x00ff, output: "done" }"#
+    );
+    assert_eq!(format!("{:?}", Event::<()>::Marker(PhantomData)), "Marker");
+
+    assert_debug::<Event<u8>>();
+    assert_debug::<Never>();
+}
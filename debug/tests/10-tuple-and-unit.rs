@@ -0,0 +1,48 @@
+// Tuple structs are printed with `debug_tuple` and unit structs as just their
+// name, like the standard `Debug` derive does. Positional fields accept the
+// same `#[debug = "..."]` attribute as named ones.
+//
+// Newtypes that should print as the value they wrap can be marked
+// `#[debug(transparent)]`, which requires exactly one field.
+
+use derive_debug::CustomDebug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub struct Point(i32, #[debug = "{:+}"] i32);
+
+#[derive(CustomDebug)]
+pub struct Tagged<T>(T, PhantomData<T>);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Flags {
+    #[debug = "0b{:04b}"]
+    bits: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct User {
+    id: UserId,
+    flags: Flags,
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", Point(1, 2)), "Point(1, +2)");
+    assert_eq!(format!("{:?}", Tagged("a", PhantomData)), r#"Tagged("a")"#);
+    assert_eq!(format!("{:?}", Unit), "Unit");
+    assert_eq!(format!("{:?}", UserId(7)), "7");
+
+    let user = User {
+        id: UserId(7),
+        flags: Flags { bits: 5 },
+    };
+    assert_eq!(format!("{:?}", user), "User { id: 7, flags: 0b0101 }");
+}
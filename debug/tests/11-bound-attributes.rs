@@ -0,0 +1,57 @@
+// The inferred bounds can be replaced where they are wrong for a field, for
+// example when its custom format uses a trait other than `Debug`.
+//
+// On a field, `#[debug(bound = "...")]` replaces the bounds inferred from that
+// field's type. On the struct, it replaces every inferred bound, and
+// `bound = ""` leaves the impl without any.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, Display};
+
+#[derive(CustomDebug)]
+#[debug(bound = "T: Display")]
+pub struct Label<T> {
+    #[debug = "{}"]
+    text: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Slot<T> {
+    index: usize,
+    #[debug = "{:p}"]
+    #[debug(bound = "")]
+    value: Box<T>,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "")]
+pub struct Count<T> {
+    #[debug = "{}"]
+    len: usize,
+    items: std::marker::PhantomData<T>,
+}
+
+struct Name;
+
+impl Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("name")
+    }
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_eq!(format!("{:?}", Label { text: Name }), "Label { text: name }");
+
+    let slot = Slot {
+        index: 3,
+        value: Box::new(Name),
+    };
+    let expected = format!("Slot {{ index: 3, value: {:p} }}", slot.value);
+    assert_eq!(format!("{:?}", slot), expected);
+
+    assert_debug::<Label<Name>>();
+    assert_debug::<Slot<Name>>();
+    assert_debug::<Count<Name>>();
+}
@@ -0,0 +1,67 @@
+// Mistakes in `#[debug(...)]` attributes are reported as compile errors at the
+// attribute, instead of being ignored or surfacing later as a confusing
+// failure inside the generated impl. All of them are reported at once, as are
+// keys given twice.
+//
+// Format strings are checked as well: the field is their only argument, so
+// they must have at least one placeholder, every placeholder must refer to
+// the field, and the spec of each must be valid.
+//
+// Enum variants take no debug attributes of their own.
+//
+// `each` only applies to collections it knows how to walk, and formats their
+// elements, so it cannot be combined with a format string for the whole field.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Register {
+    #[debug = "{:08b} and {:08b}"]
+    twice: u8,
+    #[debug = "{value:x}"]
+    named: u8,
+    #[debug = "{:y}"]
+    spec: u8,
+    #[debug = "no placeholder"]
+    missing: u8,
+    #[debug = "{:?"]
+    unterminated: u8,
+    #[debug(skp, bogus = 1)]
+    unknown: u8,
+    #[debug = "{:x}"]
+    #[debug = "{:b}"]
+    twice_formatted: u8,
+    #[debug(redact)]
+    #[debug(redact = "***")]
+    twice_redacted: u8,
+    /// The last key shown.
+    #[debug(skip, redact)]
+    hidden: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Dump {
+    #[debug(each = "{:x}")]
+    scalar: u32,
+    #[debug = "{:?}"]
+    #[debug(each = "{:x}")]
+    both: Vec<u32>,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    #[debug(skip)]
+    Tick,
+    #[debug(bogus = "x")]
+    Key(char),
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Pair(u8, u8);
+
+#[derive(CustomDebug)]
+#[debug(bonud = "", transparnt)]
+pub struct Misspelled;
+
+fn main() {}
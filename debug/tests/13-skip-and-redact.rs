@@ -0,0 +1,65 @@
+// Fields can be kept out of the output, for example to keep secrets out of
+// logs.
+//
+// `#[debug(skip)]` leaves a field out entirely, and the output ends in `..`
+// so that readers know something was omitted. `#[debug(redact)]` prints
+// `<redacted>` in place of the value, or the given placeholder with
+// `#[debug(redact = "...")]`.
+//
+// Neither kind of field is formatted, so its type does not have to implement
+// `Debug` and adds no bounds to the impl.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub struct Connection;
+
+#[derive(CustomDebug)]
+pub struct Credentials<C> {
+    user: &'static str,
+    #[debug(redact)]
+    password: String,
+    #[debug(redact = "<hidden>")]
+    token: String,
+    #[debug(skip)]
+    connection: C,
+}
+
+#[derive(CustomDebug)]
+pub enum Auth {
+    Anonymous,
+    Token(#[debug(redact)] String),
+    Session(u64, #[debug(skip)] Connection),
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Secret(#[debug(redact = "***")] String);
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let credentials = Credentials {
+        user: "admin",
+        password: "hunter2".to_owned(),
+        token: "abc".to_owned(),
+        connection: Connection,
+    };
+    assert_eq!(
+        format!("{:?}", credentials),
+        r#"Credentials { user: "admin", password: <redacted>, token: <hidden>, .. }"#
+    );
+
+    assert_eq!(format!("{:?}", Auth::Anonymous), "Anonymous");
+    assert_eq!(
+        format!("{:?}", Auth::Token("abc".to_owned())),
+        "Token(<redacted>)"
+    );
+    assert_eq!(
+        format!("{:?}", Auth::Session(7, Connection)),
+        "Session(7, ..)"
+    );
+    assert_eq!(format!("{:?}", Secret("hunter2".to_owned())), "***");
+
+    assert_debug::<Credentials<Connection>>();
+}
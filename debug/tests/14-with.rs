@@ -0,0 +1,72 @@
+// When a format string is not enough, a field can be rendered by a function
+// with `#[debug(with = path::to::function)]`. The function has the signature
+// of `Debug::fmt`:
+//
+//     fn(&T, &mut fmt::Formatter) -> fmt::Result
+//
+// The field is not formatted through `Debug`, so its type adds no bounds to
+// the impl; the function decides what it needs.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::time::Duration;
+
+mod render {
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn duration(duration: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ms", duration.as_millis())
+    }
+
+    pub fn len<T>(items: &Vec<T>, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{} items]", items.len())
+    }
+}
+
+fn request_id(id: &u64, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "req-{:06}", id)
+}
+
+#[derive(CustomDebug)]
+pub struct Request<T> {
+    #[debug(with = request_id)]
+    id: u64,
+    #[debug(with = render::duration)]
+    elapsed: Duration,
+    #[debug(with = render::len)]
+    body: Vec<T>,
+}
+
+#[derive(CustomDebug)]
+pub enum Timeout {
+    After(#[debug(with = render::duration)] Duration),
+    Never,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Elapsed(#[debug(with = render::duration)] Duration);
+
+pub struct Opaque;
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let request = Request {
+        id: 42,
+        elapsed: Duration::from_millis(1500),
+        body: vec![Opaque, Opaque],
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        "Request { id: req-000042, elapsed: 1500ms, body: [2 items] }"
+    );
+
+    let timeout = Timeout::After(Duration::from_secs(2));
+    assert_eq!(format!("{:?}", timeout), "After(2000ms)");
+    assert_eq!(format!("{:?}", Timeout::Never), "Never");
+    assert_eq!(format!("{:?}", Elapsed(Duration::from_millis(5))), "5ms");
+
+    assert_debug::<Request<Opaque>>();
+}
@@ -0,0 +1,111 @@
+// The flags given to the whole value reach every field, the same as with
+// `#[derive(Debug)]`: `{:#?}` pretty-prints nested values, and a width or
+// precision applies to each field. Without attributes the output matches the
+// standard derive exactly.
+//
+// A custom format string inherits those flags where its own placeholders
+// leave them unset, so `#[debug = "{:?}ms"]` still pretty-prints under
+// `{:#?}`, and `{:.1?}` still limits the digits of a float.
+
+use derive_debug::CustomDebug;
+
+mod standard {
+    #[derive(Debug)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    #[derive(Debug)]
+    pub struct Wrapper(pub Vec<Point>, pub Option<&'static str>);
+
+    #[derive(Debug)]
+    pub enum Shape {
+        Empty,
+        Polygon { points: Vec<Point> },
+        Circle(Point, f64),
+    }
+}
+
+mod custom {
+    use derive_debug::CustomDebug;
+
+    #[derive(CustomDebug)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    #[derive(CustomDebug)]
+    pub struct Wrapper(pub Vec<Point>, pub Option<&'static str>);
+
+    #[derive(CustomDebug)]
+    pub enum Shape {
+        Empty,
+        Polygon { points: Vec<Point> },
+        Circle(Point, f64),
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Timing {
+    #[debug = "{:?}ms"]
+    samples: Vec<f64>,
+    #[debug = "{:>8}"]
+    label: &'static str,
+}
+
+fn check<S: std::fmt::Debug, C: std::fmt::Debug>(standard: S, custom: C) {
+    assert_eq!(format!("{:?}", standard), format!("{:?}", custom));
+    assert_eq!(format!("{:#?}", standard), format!("{:#?}", custom));
+    assert_eq!(format!("{:8?}", standard), format!("{:8?}", custom));
+    assert_eq!(format!("{:.1?}", standard), format!("{:.1?}", custom));
+    assert_eq!(format!("{:#.3?}", standard), format!("{:#.3?}", custom));
+}
+
+fn main() {
+    check(
+        standard::Point { x: 1.0, y: 2.25 },
+        custom::Point { x: 1.0, y: 2.25 },
+    );
+    check(
+        standard::Wrapper(vec![standard::Point { x: 0.5, y: 1.0 }], Some("a")),
+        custom::Wrapper(vec![custom::Point { x: 0.5, y: 1.0 }], Some("a")),
+    );
+    check(standard::Shape::Empty, custom::Shape::Empty);
+    check(
+        standard::Shape::Polygon {
+            points: vec![standard::Point { x: 1.0, y: 1.0 }],
+        },
+        custom::Shape::Polygon {
+            points: vec![custom::Point { x: 1.0, y: 1.0 }],
+        },
+    );
+    check(
+        standard::Shape::Circle(standard::Point { x: 0.0, y: 0.0 }, 1.5),
+        custom::Shape::Circle(custom::Point { x: 0.0, y: 0.0 }, 1.5),
+    );
+
+    let timing = Timing {
+        samples: vec![1.25, 2.5],
+        label: "run",
+    };
+    assert_eq!(
+        format!("{:?}", timing),
+        r#"Timing { samples: [1.25, 2.5]ms, label:      run }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", timing),
+        r#"Timing {
+    samples: [
+        1.25,
+        2.5,
+    ]ms,
+    label:      run,
+}"#,
+    );
+    assert_eq!(
+        format!("{:.1?}", timing),
+        r#"Timing { samples: [1.2, 2.5]ms, label:      run }"#,
+    );
+}
@@ -0,0 +1,52 @@
+// Type parameters are found wherever they appear in a field's type: behind
+// references, inside tuples, arrays and slices, and in the arguments of other
+// types. Each one that is formatted gets a `Debug` bound.
+//
+// Raw pointers and function pointers print as addresses, so a parameter that
+// only appears inside one of them needs no bound at all.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Shapes<'a, A, B, C, D, E> {
+    reference: &'a A,
+    pair: (B, u8),
+    array: [C; 2],
+    slice: Box<[D]>,
+    nested: Option<&'a [(E, E)]>,
+}
+
+#[derive(CustomDebug)]
+pub struct Pointers<F, G> {
+    callback: fn(F) -> F,
+    raw: *const G,
+}
+
+fn assert_debug<T: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Pointers<NotDebug, NotDebug>>();
+
+    let shapes = Shapes {
+        reference: &1,
+        pair: ("b", 2),
+        array: ['c', 'd'],
+        slice: Box::new([true]),
+        nested: Some(&[(0.5, 1.5)]),
+    };
+    let expected = r#"Shapes { reference: 1, pair: ("b", 2), array: ['c', 'd'], slice: [true], nested: Some([(0.5, 1.5)]) }"#;
+    assert_eq!(format!("{:?}", shapes), expected);
+
+    fn double(x: u8) -> u8 {
+        x * 2
+    }
+    let pointers = Pointers::<u8, u8> {
+        callback: double,
+        raw: std::ptr::null(),
+    };
+    assert!(format!("{:?}", pointers).starts_with("Pointers { callback: 0x"));
+}
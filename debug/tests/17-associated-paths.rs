@@ -0,0 +1,68 @@
+// Associated types are recognized in every spelling: `T::Value`, the fully
+// qualified `<T as Trait>::Value`, associated types of associated types such
+// as `<T::Assoc as Inner>::Nested`, and inside the arguments of other types
+// such as `HashMap<T::Key, T::Value>`.
+// Each distinct associated type gets one `Debug` predicate, merged with any
+// bounds it already has in the where-clause, while `T` itself stays unbounded.
+
+use derive_debug::CustomDebug;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+pub trait Inner {
+    type Nested;
+}
+
+pub trait Trait {
+    type Key;
+    type Value;
+    type Assoc: Inner;
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait>
+where
+    T::Value: Clone,
+{
+    map: HashMap<T::Key, T::Value>,
+    qualified: <T as Trait>::Value,
+    values: Vec<T::Value>,
+    nested: Option<<T::Assoc as Inner>::Nested>,
+}
+
+#[derive(CustomDebug)]
+pub enum Either<T: Trait, U: Trait> {
+    Left(Box<[T::Key]>),
+    Right(Box<<U as Trait>::Value>, (T::Key, U::Key)),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated types do.
+    struct Id;
+
+    struct Unit;
+
+    impl Inner for Unit {
+        type Nested = bool;
+    }
+
+    impl Trait for Id {
+        type Key = &'static str;
+        type Value = u8;
+        type Assoc = Unit;
+    }
+
+    assert_debug::<Field<Id>>();
+    assert_debug::<Either<Id, Id>>();
+
+    let field = Field::<Id> {
+        map: HashMap::from([("a", 1)]),
+        qualified: 2,
+        values: vec![3],
+        nested: Some(true),
+    };
+    let expected = r#"Field { map: {"a": 1}, qualified: 2, values: [3], nested: Some(true) }"#;
+    assert_eq!(format!("{:?}", field), expected);
+}
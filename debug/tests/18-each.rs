@@ -0,0 +1,80 @@
+// A format string given with `#[debug(each = "...")]` applies to every element
+// of a collection field, while the collection keeps its usual rendering:
+// brackets for a `Vec`, slice or array, braces for a map, whose keys are
+// printed as usual, and `Some(..)` for an `Option`.
+//
+// Collections behind references, `Box`, `Rc` and `Arc` are recognized too.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+pub struct Registers<'a> {
+    #[debug(each = "{:#010x}")]
+    dump: Vec<u32>,
+    #[debug(each = "{:04b}")]
+    flags: [u8; 2],
+    #[debug(each = "{:x}")]
+    window: &'a [u16],
+    #[debug(each = "{:.1}")]
+    averages: Box<[f64]>,
+    #[debug(each = "0x{:x}")]
+    named: BTreeMap<&'static str, u32>,
+    #[debug(each = "{:#x}")]
+    fault: Option<u32>,
+    #[debug(each = "{:#x}")]
+    clear: Option<u32>,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Dump(#[debug(each = "{:02x}")] Vec<u8>);
+
+fn main() {
+    let registers = Registers {
+        dump: vec![0xdead, 0xbeef],
+        flags: [1, 10],
+        window: &[255, 16],
+        averages: Box::new([0.25, 1.0]),
+        named: BTreeMap::from([("pc", 0x40), ("sp", 0xff00)]),
+        fault: Some(0x1f),
+        clear: None,
+    };
+
+    let debug = format!("{:?}", registers);
+    let expected = concat!(
+        "Registers { dump: [0x0000dead, 0x0000beef], flags: [0001, 1010], ",
+        "window: [ff, 10], averages: [0.2, 1.0], ",
+        r#"named: {"pc": 0x40, "sp": 0xff00}, fault: Some(0x1f), clear: None }"#,
+    );
+    assert_eq!(debug, expected);
+
+    let pretty = format!("{:#?}", Registers {
+        dump: vec![1],
+        flags: [0, 0],
+        window: &[],
+        averages: Box::new([]),
+        named: BTreeMap::new(),
+        fault: Some(2),
+        clear: None,
+    });
+    let expected = r#"Registers {
+    dump: [
+        0x00000001,
+    ],
+    flags: [
+        0000,
+        0000,
+    ],
+    window: [],
+    averages: [],
+    named: {},
+    fault: Some(
+        0x2,
+    ),
+    clear: None,
+}"#;
+    assert_eq!(pretty, expected);
+
+    assert_eq!(format!("{:?}", Dump(vec![1, 171])), "[01, ab]");
+}
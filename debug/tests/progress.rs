@@ -9,4 +9,14 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-tuple-and-unit.rs");
+    t.pass("tests/11-bound-attributes.rs");
+    t.compile_fail("tests/12-attribute-errors.rs");
+    t.pass("tests/13-skip-and-redact.rs");
+    t.pass("tests/14-with.rs");
+    t.pass("tests/15-pretty.rs");
+    t.pass("tests/16-type-shapes.rs");
+    t.pass("tests/17-associated-paths.rs");
+    t.pass("tests/18-each.rs");
 }